@@ -0,0 +1,82 @@
+//! Kerberos key usage numbers
+//!
+//! The key usage number is used when deriving the key for each encrypted or checksummed part
+//! of a message, so decrypting a part requires using the matching key usage.
+//!
+//! - [RFC4120](https://tools.ietf.org/html/rfc4120#section-7.5.1) Key Usage Numbers
+
+/// AS-REQ PA-ENC-TIMESTAMP padata timestamp, encrypted with the client key
+pub const AS_REQ_PA_ENC_TIMESTAMP: i32 = 1;
+/// AS-REP Ticket and TGS-REP Ticket, encrypted with the service key
+pub const KDC_REP_TICKET: i32 = 2;
+/// AS-REP encrypted part, encrypted with the client key
+pub const AS_REP_ENC_PART: i32 = 3;
+/// TGS-REQ KDC-REQ-BODY AuthorizationData, encrypted with the TGS session key
+pub const TGS_REQ_AD_SESSION_KEY: i32 = 4;
+/// TGS-REQ KDC-REQ-BODY AuthorizationData, encrypted with the TGS authenticator subkey
+pub const TGS_REQ_AD_SUBKEY: i32 = 5;
+/// TGS-REQ PA-TGS-REQ padata AP-REQ Authenticator cksum, keyed with the TGS session key
+pub const TGS_REQ_AUTH_CKSUM: i32 = 6;
+/// TGS-REQ PA-TGS-REQ padata AP-REQ Authenticator, encrypted with the TGS session key
+pub const TGS_REQ_AUTH: i32 = 7;
+/// TGS-REP encrypted part, encrypted with the TGS session key
+pub const TGS_REP_ENC_PART_SESSION_KEY: i32 = 8;
+/// TGS-REP encrypted part, encrypted with the TGS authenticator subkey
+pub const TGS_REP_ENC_PART_SUBKEY: i32 = 9;
+/// AP-REQ Authenticator cksum, keyed with the application session key
+pub const AP_REQ_AUTH_CKSUM: i32 = 10;
+/// AP-REQ Authenticator, encrypted with the application session key
+pub const AP_REQ_AUTH: i32 = 11;
+/// AP-REP encrypted part, encrypted with the application session key
+pub const AP_REP_ENC_PART: i32 = 12;
+/// KRB-PRIV encrypted part, encrypted with a key chosen by the application
+pub const KRB_PRIV_ENC_PART: i32 = 13;
+/// KRB-CRED encrypted part, encrypted with a key chosen by the application
+pub const KRB_CRED_ENC_PART: i32 = 14;
+/// KRB-SAFE cksum, keyed with a key chosen by the application
+pub const KRB_SAFE_CKSUM: i32 = 15;
+/// AD-KDC-ISSUED checksum
+pub const AD_KDC_ISSUED_CKSUM: i32 = 19;
+
+/// Encrypted parts of Kerberos messages, used to select the key usage number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncPartContext {
+    /// PA-ENC-TIMESTAMP padata of an AS-REQ
+    PaEncTimestamp,
+    /// Encrypted part of an AS-REP
+    AsRepEncPart,
+    /// Encrypted part of a TGS-REP, when no subkey was sent in the authenticator
+    TgsRepEncPartSessionKey,
+    /// Encrypted part of a TGS-REP, when a subkey was sent in the authenticator
+    TgsRepEncPartSubkey,
+    /// Encrypted part of a Ticket
+    Ticket,
+    /// Authenticator of the AP-REQ in the PA-TGS-REQ padata of a TGS-REQ
+    TgsReqAuthenticator,
+    /// Authenticator of an AP-REQ
+    ApReqAuthenticator,
+    /// Encrypted part of an AP-REP
+    ApRepEncPart,
+    /// Encrypted part of a KRB-PRIV
+    KrbPriv,
+    /// Encrypted part of a KRB-CRED
+    KrbCred,
+}
+
+impl EncPartContext {
+    /// Get the key usage number used to encrypt this part
+    pub fn key_usage(self) -> i32 {
+        match self {
+            EncPartContext::PaEncTimestamp => AS_REQ_PA_ENC_TIMESTAMP,
+            EncPartContext::AsRepEncPart => AS_REP_ENC_PART,
+            EncPartContext::TgsRepEncPartSessionKey => TGS_REP_ENC_PART_SESSION_KEY,
+            EncPartContext::TgsRepEncPartSubkey => TGS_REP_ENC_PART_SUBKEY,
+            EncPartContext::Ticket => KDC_REP_TICKET,
+            EncPartContext::TgsReqAuthenticator => TGS_REQ_AUTH,
+            EncPartContext::ApReqAuthenticator => AP_REQ_AUTH,
+            EncPartContext::ApRepEncPart => AP_REP_ENC_PART,
+            EncPartContext::KrbPriv => KRB_PRIV_ENC_PART,
+            EncPartContext::KrbCred => KRB_CRED_ENC_PART,
+        }
+    }
+}
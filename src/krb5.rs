@@ -7,16 +7,27 @@
 //! - [RFC6803](https://tools.ietf.org/html/rfc6803) Camellia Encryption for Kerberos 5
 //! - [RFC8009](https://tools.ietf.org/html/rfc8009) AES Encryption with HMAC-SHA2 for Kerberos 5
 
+use der_parser::ber::BerObjectContent;
 use der_parser::der::DerObject;
 use std::fmt;
 
+#[cfg(feature = "time")]
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Utc};
+
+use crate::key_usage::EncPartContext;
 pub use crate::krb5_constants::*;
+pub use crate::krb5_encoder::TicketBuilder;
 pub use crate::krb5_errors::*;
+use crate::krb5_parser::{
+    parse_ad_and_or, parse_ad_if_relevant, parse_ad_kdc_issued, parse_authorization_data,
+    parse_etype_info2, parse_kerb_error_data_windows, parse_kerb_ext_error, parse_pa_enc_timestamp,
+    parse_pa_pac_options, parse_pa_pac_request,
+};
 
 /// Kerberos Realm
 ///
 /// A Kerberos realm is a set of managed nodes that share the same Kerberos database.
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct Realm(pub String);
 
 /// Kerberos PrincipalName
@@ -28,18 +39,74 @@ pub struct Realm(pub String);
 /// <pre>
 /// principal-name.instance-name@realm-name
 /// </pre>
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Default)]
 pub struct PrincipalName {
     pub name_type: NameType,
     pub name_string: Vec<String>,
 }
 
+impl PrincipalName {
+    /// Get the service class of a service principal name (for ex. `cifs` in `cifs/host`)
+    ///
+    /// Returns `None` if the name has less than two components.
+    pub fn service_class(&self) -> Option<&str> {
+        if self.name_string.len() < 2 {
+            return None;
+        }
+        Some(&self.name_string[0])
+    }
+
+    /// Get the host name of a `KRB_NT_SRV_XHST` principal name
+    ///
+    /// For this name type, the first component is the service, and the host name is split in
+    /// the remaining components. These are joined with `.`. Returns `None` for other name
+    /// types, or if there is no host component.
+    pub fn host_xhst(&self) -> Option<String> {
+        if self.name_type != NameType::KRB_NT_SRV_XHST || self.name_string.len() < 2 {
+            return None;
+        }
+        Some(self.name_string[1..].join("."))
+    }
+}
+
 impl fmt::Display for PrincipalName {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(&self.name_string.join("/"))
     }
 }
 
+/// Kerberos Time
+///
+/// The raw value of the GeneralizedTime, in the `YYYYMMDDHHMMSSZ` form (with no fractional
+/// seconds) required by Kerberos. `parse_kerberos_time` rejects other forms. Since this form
+/// has a fixed width, ordering the raw bytes also orders the times chronologically (this does
+/// not hold for values built from other forms).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct KerberosTime<'a>(pub &'a [u8]);
+
+impl<'a> KerberosTime<'a> {
+    /// Get the raw bytes of the time
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Convert to a `chrono` date, if the value is well-formed
+    #[cfg(feature = "time")]
+    pub fn to_datetime(self) -> Option<DateTime<Utc>> {
+        let s = std::str::from_utf8(self.0).ok()?;
+        let dt = NaiveDateTime::parse_from_str(s, "%Y%m%d%H%M%SZ").ok()?;
+        Some(Utc.from_utc_datetime(&dt))
+    }
+}
+
+impl<'a> fmt::Debug for KerberosTime<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("KerberosTime")
+            .field(&String::from_utf8_lossy(self.0))
+            .finish()
+    }
+}
+
 /// Kerberos Ticket
 ///
 /// A record that helps a client authenticate itself to a server; it
@@ -67,9 +134,125 @@ pub struct EncryptedData<'a> {
     /// Version number of the key under which data is encrypted
     pub kvno: Option<u32>,
     /// Ciphertext
+    ///
+    /// The parsers in this crate borrow the ciphertext from the input, without copying it.
     pub cipher: Cow<'a, [u8]>,
 }
 
+impl<'a> EncryptedData<'a> {
+    /// Get the key usage number to use when decrypting the given part of a message
+    pub fn key_usage_for(context: EncPartContext) -> i32 {
+        context.key_usage()
+    }
+
+    /// Return the length of the ciphertext, in bytes
+    pub fn cipher_len(&self) -> usize {
+        self.cipher.len()
+    }
+}
+
+/// Kerberos Checksum
+#[derive(Debug, PartialEq, Clone)]
+pub struct Checksum<'a> {
+    /// Checksum type
+    pub cksumtype: ChecksumType,
+    /// Checksum value
+    pub checksum: &'a [u8],
+}
+
+/// Kerberos AuthorizationData element
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuthorizationDataElement<'a> {
+    /// Authorization data type
+    pub ad_type: AdType,
+    /// Authorization data, encoding depends on `ad_type`
+    pub ad_data: &'a [u8],
+}
+
+impl<'a> AuthorizationDataElement<'a> {
+    /// Decode the nested AuthorizationData of an AD-IF-RELEVANT element
+    ///
+    /// Returns `None` if the element has another type, or if the content is invalid (including
+    /// when AD-IF-RELEVANT elements are nested too deeply).
+    pub fn as_if_relevant(&self) -> Option<AuthorizationData<'a>> {
+        if self.ad_type != AdType::AD_IF_RELEVANT {
+            return None;
+        }
+        parse_ad_if_relevant(self.ad_data).ok().map(|(_, ad)| ad)
+    }
+
+    /// Decode the content of an AD-KDCIssued element
+    ///
+    /// Returns `None` if the element has another type, or if the content is invalid.
+    pub fn as_kdc_issued(&self) -> Option<AdKdcIssued<'a>> {
+        if self.ad_type != AdType::AD_KDC_ISSUED {
+            return None;
+        }
+        parse_ad_kdc_issued(self.ad_data).ok().map(|(_, ad)| ad)
+    }
+
+    /// Decode the content of an AD-AND-OR element
+    ///
+    /// Returns `None` if the element has another type, or if the content is invalid.
+    pub fn as_and_or(&self) -> Option<AdAndOr<'a>> {
+        if self.ad_type != AdType::AD_AND_OR {
+            return None;
+        }
+        parse_ad_and_or(self.ad_data).ok().map(|(_, ad)| ad)
+    }
+
+    /// Decode the nested AuthorizationData of an AD-MANDATORY-FOR-KDC element
+    ///
+    /// Returns `None` if the element has another type, or if the content is invalid.
+    pub fn as_mandatory_for_kdc(&self) -> Option<AuthorizationData<'a>> {
+        if self.ad_type != AdType::AD_MANDATORY_FOR_KDC {
+            return None;
+        }
+        parse_authorization_data(self.ad_data)
+            .ok()
+            .map(|(_, ad)| ad)
+    }
+}
+
+/// Kerberos AuthorizationData
+///
+/// Restrictions on the use of a ticket, or additional data for the server.
+pub type AuthorizationData<'a> = Vec<AuthorizationDataElement<'a>>;
+
+/// Kerberos AD-KDCIssued
+///
+/// Authorization data elements issued by the KDC, protected by a checksum.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AdKdcIssued<'a> {
+    /// Checksum of the encoding of `elements`
+    pub ad_checksum: Checksum<'a>,
+    /// Realm of the issuing principal, if different from the ticket realm
+    pub i_realm: Option<Realm>,
+    /// Name of the issuing principal, if different from the ticket server
+    pub i_sname: Option<PrincipalName>,
+    /// Issued authorization data
+    pub elements: AuthorizationData<'a>,
+}
+
+/// Kerberos AD-AND-OR
+///
+/// Authorization data elements, of which at least `condition_count` must be satisfied.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AdAndOr<'a> {
+    /// Number of elements that must be satisfied
+    pub condition_count: i32,
+    pub elements: AuthorizationData<'a>,
+}
+
+/// Kerberos EncryptionKey
+#[derive(Debug, PartialEq, Clone)]
+pub struct EncryptionKey<'a> {
+    /// Encryption type of the key
+    pub keytype: EncryptionType,
+    /// Key material
+    pub keyvalue: &'a [u8],
+}
+
 /// Key Distribution Center (KDC) Request Message
 #[derive(Debug, PartialEq)]
 pub struct KdcReq<'a> {
@@ -79,6 +262,99 @@ pub struct KdcReq<'a> {
     pub req_body: KdcReqBody<'a>,
 }
 
+impl<'a> KdcReq<'a> {
+    /// Iterate over the PA-Data of the given type, in the order of the request
+    ///
+    /// A request can contain several PA-Data with the same type, so all matching entries are
+    /// returned.
+    pub fn padata_by_type<'s>(
+        &'s self,
+        padata_type: PAType,
+    ) -> impl Iterator<Item = &'s PAData<'a>> + 's {
+        self.padata
+            .iter()
+            .filter(move |p| p.padata_type == padata_type)
+    }
+
+    /// Get the PAC-related options of the request
+    ///
+    /// This combines the PA-PAC-REQUEST and PA-PAC-OPTIONS PA-Data. Options are `false` if
+    /// PA-PAC-OPTIONS is absent or invalid.
+    pub fn pac_behavior(&self) -> PacBehavior {
+        let flags = self
+            .padata_by_type(PAType::PA_PAC_OPTIONS)
+            .next()
+            .and_then(|p| parse_pa_pac_options(p.padata_value).ok())
+            .map(|(_, flags)| flags);
+        let is_set = |n| {
+            flags
+                .as_ref()
+                .map(|f| kerberos_flag_is_set(f, n))
+                .unwrap_or(false)
+        };
+        PacBehavior {
+            include_pac: self.pac_requested(),
+            claims: is_set(0),
+            branch_aware: is_set(1),
+            forward_to_full_dc: is_set(2),
+            resource_based_constrained_delegation: is_set(3),
+        }
+    }
+
+    /// Test if the request is for user-to-user authentication
+    ///
+    /// This requires the ENC-TKT-IN-SKEY option, and an additional ticket (the session key
+    /// of which is used to encrypt the issued ticket).
+    pub fn is_user_to_user(&self) -> bool {
+        kerberos_flag_is_set(&self.req_body.kdc_options, 28)
+            && !self.req_body.additional_tickets.is_empty()
+    }
+
+    /// Get the nonce of the request (see `KdcReqBody::nonce`)
+    ///
+    /// The KDC echoes this value in the encrypted part of the reply, so it can be used to
+    /// match a request with its reply once the reply is decrypted.
+    pub fn nonce(&self) -> u32 {
+        self.req_body.nonce
+    }
+
+    /// Whether the client asked for a PAC to be included in the ticket
+    ///
+    /// This is the value of the PA-PAC-REQUEST PA-Data. If it is absent (or invalid), `true` is
+    /// returned, which is the default behavior of Windows KDCs.
+    pub fn pac_requested(&self) -> bool {
+        self.padata_by_type(PAType::PA_PAC_REQUEST)
+            .next()
+            .and_then(|p| parse_pa_pac_request(p.padata_value).ok())
+            .map(|(_, include_pac)| include_pac)
+            .unwrap_or(true)
+    }
+
+    /// Get the requested server name, as a SPN string (for ex. `cifs/host.example.com`)
+    ///
+    /// Components of `req_body.sname` are joined with `/`. Returns `None` if `sname` is absent.
+    pub fn target_spn(&self) -> Option<String> {
+        self.req_body.sname.as_ref().map(|s| s.to_string())
+    }
+}
+
+/// PAC-related options of a KDC request
+///
+/// See `KdcReq::pac_behavior`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacBehavior {
+    /// Value of PA-PAC-REQUEST (`true` if absent)
+    pub include_pac: bool,
+    /// The client supports claims
+    pub claims: bool,
+    /// The client supports read-only domain controllers
+    pub branch_aware: bool,
+    /// The request must be forwarded to a full (writable) domain controller
+    pub forward_to_full_dc: bool,
+    /// The client supports resource-based constrained delegation
+    pub resource_based_constrained_delegation: bool,
+}
+
 /// Key Distribution Center (KDC) Request Message Body
 #[derive(Debug, PartialEq)]
 pub struct KdcReqBody<'a> {
@@ -91,11 +367,11 @@ pub struct KdcReqBody<'a> {
     /// Server name
     pub sname: Option<PrincipalName>,
     /// Desired starttime for the requested ticket
-    pub from: Option<DerObject<'a>>,
+    pub from: Option<KerberosTime<'a>>,
     /// Expiration date requested by the client
-    pub till: DerObject<'a>,
+    pub till: KerberosTime<'a>,
     /// Requested renew-till time
-    pub rtime: Option<DerObject<'a>>,
+    pub rtime: Option<KerberosTime<'a>>,
     /// Random number generated by the client
     pub nonce: u32,
     /// Desired encryption algorithm to be used in the response
@@ -104,11 +380,69 @@ pub struct KdcReqBody<'a> {
     pub addresses: Vec<HostAddress<'a>>,
     /// Encoding of the desired authorization-data encrypted under the sub-session key if present
     /// in the Authenticator, or alternatively from the session key in the TGT
+    ///
+    /// The key usage is `key_usage::TGS_REQ_AD_SUBKEY` or `key_usage::TGS_REQ_AD_SESSION_KEY`.
+    /// Once decrypted, the content can be parsed with `parse_authorization_data`.
     pub enc_authorization_data: Option<EncryptedData<'a>>,
     /// Additional tickets MAY be optionally included in a request to the ticket-granting server
     pub additional_tickets: Vec<Ticket<'a>>,
 }
 
+impl<'a> KdcReqBody<'a> {
+    /// Test if the request is for a cross-realm TGT
+    ///
+    /// If the requested service is `krbtgt/OTHER` where `OTHER` is not the realm of the request,
+    /// returns the `(realm, OTHER)` pair. The `krbtgt` service name is compared ignoring case.
+    pub fn is_cross_realm_referral(&self) -> Option<(&str, &str)> {
+        let sname = self.sname.as_ref()?;
+        match sname.name_string.as_slice() {
+            [service, target] if is_krbtgt(service.as_bytes()) && *target != self.realm.0 => {
+                Some((self.realm.0.as_str(), target.as_str()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the encryption type preferred by the client
+    ///
+    /// The `etype` list is in preference order, so this is its first element. When it is
+    /// weaker than other encryption types of the list, this can indicate a downgrade.
+    pub fn weakest_preferred_etype(&self) -> Option<EncryptionType> {
+        self.etype.first().copied()
+    }
+
+    /// Test if the client prefers a RC4 encryption type over all others
+    pub fn prefers_rc4_first(&self) -> bool {
+        matches!(
+            self.weakest_preferred_etype(),
+            Some(EncryptionType::RC4_HMAC)
+                | Some(EncryptionType::RC4_HMAC_EXP)
+                | Some(EncryptionType::RC4_HMAC_OLD)
+                | Some(EncryptionType::RC4_HMAC_OLD_EXP)
+        )
+    }
+}
+
+impl<'a> Default for KdcReqBody<'a> {
+    /// Empty request body, with `kdc_options` set to a DER NULL object
+    fn default() -> Self {
+        KdcReqBody {
+            kdc_options: DerObject::from_obj(BerObjectContent::Null),
+            cname: None,
+            realm: Realm::default(),
+            sname: None,
+            from: None,
+            till: KerberosTime::default(),
+            rtime: None,
+            nonce: 0,
+            etype: Vec::new(),
+            addresses: Vec::new(),
+            enc_authorization_data: None,
+            additional_tickets: Vec::new(),
+        }
+    }
+}
+
 /// Kerberos HostAddress
 #[derive(Debug, PartialEq)]
 pub struct HostAddress<'a> {
@@ -121,6 +455,7 @@ pub struct HostAddress<'a> {
 pub struct KdcRep<'a> {
     pub pvno: u32,
     pub msg_type: MessageType,
+    /// Cleartext PA-Data (for ex. PA-ETYPE-INFO2 in an AS-REP)
     pub padata: Vec<PAData<'a>>,
     pub crealm: Realm,
     pub cname: PrincipalName,
@@ -128,14 +463,102 @@ pub struct KdcRep<'a> {
     pub enc_part: EncryptedData<'a>,
 }
 
+impl<'a> KdcRep<'a> {
+    /// Iterate over the PA-Data of the given type, in the order of the reply
+    ///
+    /// A reply can contain several PA-Data with the same type, so all matching entries are
+    /// returned.
+    pub fn padata_by_type<'s>(
+        &'s self,
+        padata_type: PAType,
+    ) -> impl Iterator<Item = &'s PAData<'a>> + 's {
+        self.padata
+            .iter()
+            .filter(move |p| p.padata_type == padata_type)
+    }
+
+    /// Get the client principal, in the `cname@crealm` form
+    pub fn client_principal(&self) -> String {
+        format!("{}@{}", self.cname, self.crealm.0)
+    }
+
+    /// Get the service principal of the ticket, in the `sname@realm` form
+    ///
+    /// Returns `None` if the server name of the ticket has no components.
+    pub fn service_principal(&self) -> Option<String> {
+        if self.ticket.sname.name_string.is_empty() {
+            return None;
+        }
+        Some(format!("{}@{}", self.ticket.sname, self.ticket.realm.0))
+    }
+
+    /// Check that the realm of the ticket is consistent with the client realm
+    ///
+    /// In an AS-REP, the ticket is issued by the KDC of the client realm, so both realms must be
+    /// equal. A TGS-REP can contain a ticket for another realm (for ex. a cross-realm referral),
+    /// so this always returns `true` for other message types.
+    pub fn realm_consistency(&self) -> bool {
+        self.msg_type != MessageType::KRB_AS_REP || self.ticket.realm == self.crealm
+    }
+
+    /// Test if the reply refers the client to another realm (RFC6806)
+    ///
+    /// `enc_part` is the decrypted part of this reply. The outer `crealm` is compared with the
+    /// `srealm` and `sname` of `enc_part`: if the issued ticket is `krbtgt/OTHER`, where `OTHER`
+    /// is neither the client realm nor the issuing realm, returns the `(srealm, OTHER)` pair.
+    /// The `krbtgt` service name is compared ignoring case.
+    pub fn is_referral<'b>(&self, enc_part: &'b EncKdcRepPart) -> Option<(&'b str, &'b str)> {
+        match enc_part.sname.name_string.as_slice() {
+            [service, target]
+                if is_krbtgt(service.as_bytes())
+                    && *target != self.crealm.0
+                    && *target != enc_part.srealm.0 =>
+            {
+                Some((enc_part.srealm.0.as_str(), target.as_str()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Decode the PA-ETYPE-INFO2 PA-Data of the reply, if present
+    ///
+    /// In an AS-REP, this is where the KDC sends the salt used to derive the client key (the
+    /// `enc_part` cannot contain it, since it is encrypted with this key).
+    pub fn etype_info2(&self) -> Option<Vec<ETypeInfo2Entry<'a>>> {
+        self.padata_by_type(PAType::PA_ETYPE_INFO2)
+            .next()
+            .and_then(|p| parse_etype_info2(p.padata_value).ok())
+            .map(|(_, entries)| entries)
+    }
+}
+
+/// Detect if a KDC reply used a weaker encryption type than one offered by the client
+///
+/// The encryption type of the ticket in the reply is compared to the list of encryption types
+/// of the request. If a stronger encryption type was offered, the chosen encryption type and
+/// the offered list are returned. This is typical of RC4 tickets issued for service accounts
+/// without AES keys, or requested by tools used for Kerberoasting.
+pub fn negotiated_downgrade<'r>(
+    req: &'r KdcReq,
+    rep: &KdcRep,
+) -> Option<(EncryptionType, &'r [EncryptionType])> {
+    let chosen = rep.ticket.enc_part.etype;
+    let offered = &req.req_body.etype;
+    if offered.iter().any(|e| e.strength() > chosen.strength()) {
+        Some((chosen, offered))
+    } else {
+        None
+    }
+}
+
 /// Kerberos Error message
 #[derive(Debug, PartialEq)]
 pub struct KrbError<'a> {
     pub pvno: u32,
     pub msg_type: MessageType,
-    pub ctime: Option<DerObject<'a>>,
+    pub ctime: Option<KerberosTime<'a>>,
     pub cusec: Option<u32>,
-    pub stime: DerObject<'a>,
+    pub stime: KerberosTime<'a>,
     pub susec: u32,
     pub error_code: ErrorCode,
     pub crealm: Option<Realm>,
@@ -146,6 +569,105 @@ pub struct KrbError<'a> {
     pub edata: Option<DerObject<'a>>,
 }
 
+#[cfg(feature = "time")]
+impl<'a> KrbError<'a> {
+    /// Clock skew between client and server (`stime - ctime`)
+    ///
+    /// Returns `None` if `ctime` is absent or one of the times cannot be decoded. This is
+    /// mostly relevant for `KRB_AP_ERR_SKEW` errors.
+    pub fn clock_skew(&self) -> Option<Duration> {
+        let ctime = self.ctime?.to_datetime()?;
+        let stime = self.stime.to_datetime()?;
+        Some(stime - ctime)
+    }
+}
+
+impl<'a> KrbError<'a> {
+    /// Convert the error to a `Result`
+    ///
+    /// `KDC_ERR_NONE` is converted to `Ok(())`. Other error codes are returned as `Err`, with
+    /// the e-text field, if present.
+    pub fn as_result(&self) -> Result<(), (ErrorCode, Option<&str>)> {
+        if self.error_code == ErrorCode::KDC_ERR_NONE {
+            Ok(())
+        } else {
+            Err((self.error_code, self.etext.as_deref()))
+        }
+    }
+
+    /// Get the account state indicated by this error, if any
+    ///
+    /// If the e-data contains a Windows extended error with an account-related status, it is
+    /// used. Otherwise, the error code is used (see `ErrorCode::account_state_hint`).
+    pub fn account_state_hint(&self) -> Option<AccountState> {
+        self.kerb_error_data()
+            .and_then(|d| d.extended_error())
+            .and_then(|e| e.account_state_hint())
+            .or_else(|| self.error_code.account_state_hint())
+    }
+
+    /// Decode the e-data field as a Windows KERB-ERROR-DATA structure
+    ///
+    /// Returns `None` if e-data is absent, or does not contain a KERB-ERROR-DATA.
+    pub fn kerb_error_data(&self) -> Option<KerbErrorData> {
+        let edata = self.edata.as_ref()?.as_slice().ok()?;
+        parse_kerb_error_data_windows(edata).ok().map(|(_, d)| d)
+    }
+}
+
+/// Windows KERB-ERROR-DATA ([MS-KILE] 2.2.2)
+///
+/// Sent by Windows KDCs in the e-data field of some KRB-ERROR messages, instead of a METHOD-DATA.
+#[derive(Debug, PartialEq, Clone)]
+pub struct KerbErrorData<'a> {
+    /// Type of the data (for ex. `KERB_ERR_TYPE_EXTENDED`)
+    pub data_type: i32,
+    pub data_value: Option<&'a [u8]>,
+}
+
+impl<'a> KerbErrorData<'a> {
+    pub const KERB_AP_ERR_TYPE_SKEW_RECOVERY: i32 = 2;
+    pub const KERB_ERR_TYPE_EXTENDED: i32 = 3;
+
+    /// Decode the data value as a KERB-EXT-ERROR, if the data type is `KERB_ERR_TYPE_EXTENDED`
+    pub fn extended_error(&self) -> Option<KerbExtError> {
+        if self.data_type != Self::KERB_ERR_TYPE_EXTENDED {
+            return None;
+        }
+        parse_kerb_ext_error(self.data_value?).ok().map(|(_, e)| e)
+    }
+}
+
+/// Windows KERB-EXT-ERROR ([MS-KILE] 2.2.1)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct KerbExtError {
+    /// Extended error code (NTSTATUS)
+    pub status: u32,
+    pub reserved: u32,
+    pub flags: u32,
+}
+
+impl KerbExtError {
+    pub const STATUS_PASSWORD_EXPIRED: u32 = 0xc000_0071;
+    pub const STATUS_ACCOUNT_DISABLED: u32 = 0xc000_0072;
+    pub const STATUS_ACCOUNT_EXPIRED: u32 = 0xc000_0193;
+    pub const STATUS_PASSWORD_MUST_CHANGE: u32 = 0xc000_0224;
+    pub const STATUS_ACCOUNT_LOCKED_OUT: u32 = 0xc000_0234;
+
+    /// Get the account state indicated by the status, if any
+    pub fn account_state_hint(&self) -> Option<AccountState> {
+        match self.status {
+            Self::STATUS_PASSWORD_EXPIRED | Self::STATUS_PASSWORD_MUST_CHANGE => {
+                Some(AccountState::PasswordExpired)
+            }
+            Self::STATUS_ACCOUNT_DISABLED => Some(AccountState::Disabled),
+            Self::STATUS_ACCOUNT_EXPIRED => Some(AccountState::Expired),
+            Self::STATUS_ACCOUNT_LOCKED_OUT => Some(AccountState::Locked),
+            _ => None,
+        }
+    }
+}
+
 /// Kerberos PA-Data
 #[derive(Debug, PartialEq)]
 pub struct PAData<'a> {
@@ -153,6 +675,40 @@ pub struct PAData<'a> {
     pub padata_value: &'a [u8],
 }
 
+impl<'a> PAData<'a> {
+    /// Build a PA-Data entry from its type and (already encoded) value
+    pub fn new(padata_type: PAType, padata_value: &'a [u8]) -> Self {
+        PAData {
+            padata_type,
+            padata_value,
+        }
+    }
+
+    /// Decode the value of a PA-ENC-TIMESTAMP PA-Data
+    ///
+    /// Returns the encrypted PA-ENC-TS-ENC, or `None` if the PA-Data has another type or if the
+    /// value is invalid.
+    pub fn decode_enc_timestamp(&self) -> Option<EncryptedData<'a>> {
+        if self.padata_type != PAType::PA_ENC_TS {
+            return None;
+        }
+        parse_pa_enc_timestamp(self.padata_value)
+            .ok()
+            .map(|(_, enc)| enc)
+    }
+}
+
+/// Kerberos PA-ENC-TS-ENC
+///
+/// This is the decrypted content of a PA-ENC-TIMESTAMP (or PA-ENCRYPTED-CHALLENGE) PA-Data.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PaEncTsEnc<'a> {
+    /// Current time on the client's host
+    pub patimestamp: KerberosTime<'a>,
+    /// Microsecond part of the timestamp
+    pub pausec: Option<u32>,
+}
+
 /// Kerberos AP Request
 #[derive(Debug, PartialEq, Clone)]
 pub struct ApReq<'a> {
@@ -163,6 +719,15 @@ pub struct ApReq<'a> {
     pub authenticator: Cow<'a, EncryptedData<'a>>,
 }
 
+impl<'a> ApReq<'a> {
+    /// Test if the client requires mutual authentication (MUTUAL-REQUIRED option)
+    ///
+    /// If `false`, no AP-REP is expected from the server.
+    pub fn expects_reply(&self) -> bool {
+        kerberos_flag_is_set(&self.ap_options, 2)
+    }
+}
+
 /// Kerberos AP Reply
 #[derive(Debug, PartialEq, Clone)]
 pub struct ApRep<'a> {
@@ -170,3 +735,653 @@ pub struct ApRep<'a> {
     pub msg_type: MessageType,
     pub enc_part: Cow<'a, EncryptedData<'a>>,
 }
+
+/// Kerberos TransitedEncoding
+///
+/// List of the realms that took part in authenticating the client.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TransitedEncoding<'a> {
+    /// Encoding of the contents
+    pub tr_type: TransitedType,
+    pub contents: &'a [u8],
+}
+
+impl<'a> TransitedEncoding<'a> {
+    /// Decode the list of transited realms of a DOMAIN-X500-COMPRESS encoding
+    ///
+    /// The compressed names are expanded (RFC4120 section 3.3.3.2): a name ending with `.` is
+    /// completed with the previous realm, and a name starting with `/` is appended to the
+    /// previous realm. Null subfields (meaning that all intermediate realms were traversed) are
+    /// returned as empty strings.
+    ///
+    /// Returns `None` if the encoding type is not DOMAIN-X500-COMPRESS, or if the contents are
+    /// not valid.
+    pub fn realms(&self) -> Option<Vec<String>> {
+        if self.tr_type != TransitedType::DOMAIN_X500_COMPRESS {
+            return None;
+        }
+        let contents = std::str::from_utf8(self.contents).ok()?;
+        if contents.is_empty() {
+            return Some(Vec::new());
+        }
+        // split on unescaped commas, and remember whether the first and last characters of
+        // each name were escaped
+        let mut fields = Vec::new();
+        let mut name = String::new();
+        let mut first_escaped = false;
+        let mut last_escaped = false;
+        let mut chars = contents.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => {
+                    let c = chars.next()?;
+                    if name.is_empty() {
+                        first_escaped = true;
+                    }
+                    name.push(c);
+                    last_escaped = true;
+                }
+                ',' => {
+                    fields.push((name, first_escaped, last_escaped));
+                    name = String::new();
+                    first_escaped = false;
+                    last_escaped = false;
+                }
+                c => {
+                    name.push(c);
+                    last_escaped = false;
+                }
+            }
+        }
+        fields.push((name, first_escaped, last_escaped));
+        // expand names, relative to the previous realm
+        let mut realms: Vec<String> = Vec::with_capacity(fields.len());
+        let mut previous = String::new();
+        for (name, first_escaped, last_escaped) in fields {
+            if name.is_empty() {
+                realms.push(name);
+                continue;
+            }
+            let realm = if !first_escaped && name.starts_with(' ') {
+                name[1..].to_string()
+            } else if !first_escaped && name.starts_with('/') {
+                previous + &name
+            } else if !last_escaped && name.ends_with('.') {
+                name + &previous
+            } else {
+                name
+            };
+            previous = realm.clone();
+            realms.push(realm);
+        }
+        Some(realms)
+    }
+}
+
+// Names of the ticket flags, starting from bit 1
+const TICKET_FLAG_NAMES: &[&str] = &[
+    "forwardable",
+    "forwarded",
+    "proxiable",
+    "proxy",
+    "may-postdate",
+    "postdated",
+    "invalid",
+    "renewable",
+    "initial",
+    "pre-authent",
+    "hw-authent",
+    "transited-policy-checked",
+    "ok-as-delegate",
+];
+
+/// Kerberos TicketFlags
+///
+/// Typed view of the `flags` field of an EncTicketPart or an EncKDCRepPart. Bit 0 is the most
+/// significant bit (RFC4120 section 5.3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TicketFlags(pub u32);
+
+impl TicketFlags {
+    /// Build the flags from a KerberosFlags bit string
+    ///
+    /// Bits after the 32nd are ignored, and missing bits are unset.
+    pub fn from_der(flags: &DerObject) -> TicketFlags {
+        let mut v = 0;
+        if let BerObjectContent::BitString(_, ref b) = flags.content {
+            for (i, byte) in b.data.iter().take(4).enumerate() {
+                v |= u32::from(*byte) << (24 - 8 * i);
+            }
+        }
+        TicketFlags(v)
+    }
+
+    /// Test if bit `n` is set
+    pub fn is_set(&self, n: u32) -> bool {
+        n < 32 && self.0 & (0x8000_0000 >> n) != 0
+    }
+
+    /// Get the names of the flags that are set (for ex. `["forwardable", "renewable"]`)
+    ///
+    /// Names are those of RFC4120, in bit order. Reserved and unknown bits are ignored.
+    pub fn names(&self) -> Vec<&'static str> {
+        TICKET_FLAG_NAMES
+            .iter()
+            .enumerate()
+            .filter(|&(n, _)| self.is_set(n as u32 + 1))
+            .map(|(_, &name)| name)
+            .collect()
+    }
+
+    /// Test if the FORWARDABLE flag is set
+    pub fn is_forwardable(&self) -> bool {
+        self.is_set(1)
+    }
+
+    /// Test if the FORWARDED flag is set
+    pub fn is_forwarded(&self) -> bool {
+        self.is_set(2)
+    }
+
+    /// Test if the PROXIABLE flag is set
+    pub fn is_proxiable(&self) -> bool {
+        self.is_set(3)
+    }
+
+    /// Test if the PROXY flag is set
+    pub fn is_proxy(&self) -> bool {
+        self.is_set(4)
+    }
+
+    /// Test if the MAY-POSTDATE flag is set
+    pub fn is_may_postdate(&self) -> bool {
+        self.is_set(5)
+    }
+
+    /// Test if the POSTDATED flag is set
+    pub fn is_postdated(&self) -> bool {
+        self.is_set(6)
+    }
+
+    /// Test if the INVALID flag is set
+    ///
+    /// Postdated tickets are issued invalid, and must be validated by the KDC before use.
+    pub fn is_invalid(&self) -> bool {
+        self.is_set(7)
+    }
+
+    /// Test if the RENEWABLE flag is set
+    pub fn is_renewable(&self) -> bool {
+        self.is_set(8)
+    }
+
+    /// Test if the INITIAL flag is set
+    pub fn is_initial(&self) -> bool {
+        self.is_set(9)
+    }
+
+    /// Test if the PRE-AUTHENT flag is set
+    pub fn is_pre_authent(&self) -> bool {
+        self.is_set(10)
+    }
+
+    /// Test if the HW-AUTHENT flag is set
+    pub fn is_hw_authent(&self) -> bool {
+        self.is_set(11)
+    }
+
+    /// Test if the TRANSITED-POLICY-CHECKED flag is set
+    pub fn is_transited_policy_checked(&self) -> bool {
+        self.is_set(12)
+    }
+
+    /// Test if the OK-AS-DELEGATE flag is set
+    ///
+    /// The KDC sets this flag if the service is trusted for (unconstrained) delegation. The flag
+    /// is only available in the decrypted EncTicketPart or EncKDCRepPart, not in a Ticket.
+    pub fn is_ok_as_delegate(&self) -> bool {
+        self.is_set(13)
+    }
+}
+
+/// Kerberos EncTicketPart
+///
+/// This is the decrypted content of the `enc_part` field of a Ticket.
+#[derive(Debug, PartialEq)]
+pub struct EncTicketPart<'a> {
+    /// Ticket flags
+    pub flags: DerObject<'a>, // TicketFlags
+    /// Session key
+    pub key: EncryptionKey<'a>,
+    /// Client realm
+    pub crealm: Realm,
+    /// Client name
+    pub cname: PrincipalName,
+    /// Realms that took part in authenticating the client
+    pub transited: TransitedEncoding<'a>,
+    /// Time of the initial authentication
+    pub authtime: KerberosTime<'a>,
+    /// Time after which the ticket is valid
+    pub starttime: Option<KerberosTime<'a>>,
+    /// Expiration time of the ticket
+    pub endtime: KerberosTime<'a>,
+    /// Maximum end time that may be included in a renewal
+    pub renew_till: Option<KerberosTime<'a>>,
+    /// Addresses from which the ticket can be used (empty if absent)
+    pub caddr: Vec<HostAddress<'a>>,
+    /// Restrictions on the use of the ticket
+    pub authorization_data: Option<AuthorizationData<'a>>,
+}
+
+impl<'a> EncTicketPart<'a> {
+    /// Get the ticket flags
+    pub fn flags_typed(&self) -> TicketFlags {
+        TicketFlags::from_der(&self.flags)
+    }
+
+    /// Test if the RENEWABLE flag is set
+    pub fn is_renewable(&self) -> bool {
+        self.flags_typed().is_renewable()
+    }
+
+    /// Get the `renew_till` time, as a `chrono` date
+    ///
+    /// Returns `Ok(None)` if `renew_till` is absent. A `renew_till` time without the RENEWABLE
+    /// flag is inconsistent (and can indicate a forged ticket), and is reported as an error.
+    #[cfg(feature = "time")]
+    pub fn renew_till_utc(&self) -> Result<Option<DateTime<Utc>>, TicketTimeError> {
+        renew_till_utc(self.flags_typed(), self.renew_till)
+    }
+
+    /// Test if the ticket is postdated
+    ///
+    /// The ticket is postdated if its `starttime` is after `now`, or if the POSTDATED flag is
+    /// set. Returns `None` if `starttime` is absent or cannot be decoded.
+    #[cfg(feature = "time")]
+    pub fn is_postdated(&self, now: DateTime<Utc>) -> Option<bool> {
+        is_postdated(self.flags_typed(), self.starttime, now)
+    }
+
+    /// Test if the authorization data contains a PAC
+    ///
+    /// The PAC (AD-WIN2K-PAC) is searched at the top level and in AD-IF-RELEVANT elements, where
+    /// Windows KDCs put it.
+    pub fn has_pac(&self) -> bool {
+        fn contains_pac(ad: &[AuthorizationDataElement]) -> bool {
+            ad.iter().any(|e| match e.as_if_relevant() {
+                Some(ad) => contains_pac(&ad),
+                None => e.ad_type == AdType::AD_WIN2K_PAC,
+            })
+        }
+        match self.authorization_data {
+            Some(ref ad) => contains_pac(ad),
+            None => false,
+        }
+    }
+
+    /// Look for signs of a forged ticket (golden or silver ticket)
+    ///
+    /// These are heuristics: for ex. non-Windows KDCs do not add a PAC, and the lifetime of
+    /// tickets depends on the policy of the realm. See `TicketAnomaly` for the checks. Times that
+    /// cannot be decoded are ignored.
+    #[cfg(feature = "time")]
+    pub fn suspicious_indicators(&self, now: DateTime<Utc>) -> Vec<TicketAnomaly> {
+        let mut anomalies = Vec::new();
+        let authtime = self.authtime.to_datetime();
+        let start = match self.starttime {
+            Some(t) => t.to_datetime(),
+            None => authtime,
+        };
+        if let (Some(start), Some(end)) = (start, self.endtime.to_datetime()) {
+            if end - start > Duration::hours(SUSPICIOUS_TICKET_LIFETIME_HOURS) {
+                anomalies.push(TicketAnomaly::LongLifetime);
+            }
+        }
+        if let Some(authtime) = authtime {
+            if now - authtime > Duration::hours(SUSPICIOUS_AUTHTIME_AGE_HOURS) {
+                anomalies.push(TicketAnomaly::OldAuthtime);
+            }
+        }
+        if !self.has_pac() {
+            anomalies.push(TicketAnomaly::MissingPac);
+        }
+        let flags = self.flags_typed();
+        if flags.is_set(0)
+            || (flags.is_invalid() && !flags.is_postdated())
+            || (flags.is_postdated() && self.starttime.is_none())
+            || (self.renew_till.is_some() && !flags.is_renewable())
+        {
+            anomalies.push(TicketAnomaly::InconsistentFlags);
+        }
+        anomalies
+    }
+}
+
+/// Kerberos LastReq entry
+#[derive(Debug, PartialEq, Clone)]
+pub struct LastReqEntry<'a> {
+    /// Meaning of `lr_value`
+    pub lr_type: LastReqType,
+    pub lr_value: KerberosTime<'a>,
+}
+
+/// Kerberos LastReq
+///
+/// Information on the last requests of the client (or on expiration times), sent by the KDC.
+pub type LastReq<'a> = Vec<LastReqEntry<'a>>;
+
+/// Kerberos EncKDCRepPart
+///
+/// This is the decrypted content of the `enc_part` field of an AS-REP or a TGS-REP.
+#[derive(Debug, PartialEq)]
+pub struct EncKdcRepPart<'a> {
+    /// Session key
+    pub key: EncryptionKey<'a>,
+    /// Times of the last requests made by the client
+    pub last_req: LastReq<'a>,
+    /// Nonce from the request
+    pub nonce: u32,
+    /// Expiration time of the client's secret key
+    pub key_expiration: Option<KerberosTime<'a>>,
+    /// Ticket flags
+    pub flags: DerObject<'a>, // TicketFlags
+    /// Time of the initial authentication
+    pub authtime: KerberosTime<'a>,
+    /// Time after which the ticket is valid
+    pub starttime: Option<KerberosTime<'a>>,
+    /// Expiration time of the ticket
+    pub endtime: KerberosTime<'a>,
+    /// Maximum end time that may be included in a renewal
+    pub renew_till: Option<KerberosTime<'a>>,
+    /// Server realm
+    pub srealm: Realm,
+    /// Server name
+    pub sname: PrincipalName,
+    /// Addresses from which the ticket can be used (empty if absent)
+    pub caddr: Vec<HostAddress<'a>>,
+}
+
+impl<'a> EncKdcRepPart<'a> {
+    /// Get the ticket flags
+    pub fn flags_typed(&self) -> TicketFlags {
+        TicketFlags::from_der(&self.flags)
+    }
+
+    /// Test if the RENEWABLE flag is set
+    pub fn is_renewable(&self) -> bool {
+        self.flags_typed().is_renewable()
+    }
+
+    /// Get the `renew_till` time, as a `chrono` date
+    ///
+    /// Returns `Ok(None)` if `renew_till` is absent. A `renew_till` time without the RENEWABLE
+    /// flag is inconsistent (and can indicate a forged ticket), and is reported as an error.
+    #[cfg(feature = "time")]
+    pub fn renew_till_utc(&self) -> Result<Option<DateTime<Utc>>, TicketTimeError> {
+        renew_till_utc(self.flags_typed(), self.renew_till)
+    }
+
+    /// Test if the ticket is postdated
+    ///
+    /// The ticket is postdated if its `starttime` is after `now`, or if the POSTDATED flag is
+    /// set. Returns `None` if `starttime` is absent or cannot be decoded.
+    #[cfg(feature = "time")]
+    pub fn is_postdated(&self, now: DateTime<Utc>) -> Option<bool> {
+        is_postdated(self.flags_typed(), self.starttime, now)
+    }
+}
+
+/// Kerberos Authenticator
+///
+/// This is the decrypted content of the `authenticator` field of an AP-REQ.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Authenticator<'a> {
+    pub authenticator_vno: u32,
+    /// Client realm
+    pub crealm: Realm,
+    /// Client name
+    pub cname: PrincipalName,
+    /// Checksum of the application data
+    pub cksum: Option<Checksum<'a>>,
+    /// Microsecond part of the client's timestamp
+    pub cusec: u32,
+    /// Client's timestamp
+    pub ctime: KerberosTime<'a>,
+    /// Key to be used for the session, chosen by the client
+    pub subkey: Option<EncryptionKey<'a>>,
+    /// Initial sequence number to be used by KRB-PRIV or KRB-SAFE messages
+    pub seq_number: Option<u32>,
+    /// Restrictions on the use of the ticket
+    pub authorization_data: Option<AuthorizationData<'a>>,
+}
+
+/// Kerberos AP Reply encrypted part
+///
+/// This is the plaintext of the `enc_part` of an AP-REP.
+#[derive(Debug, PartialEq)]
+pub struct EncApRepPart<'a> {
+    /// Current time on the client's host (from the Authenticator)
+    pub ctime: KerberosTime<'a>,
+    /// Microsecond part of the client's timestamp (from the Authenticator)
+    pub cusec: u32,
+    /// Sub-session key to be used by the application
+    pub subkey: Option<EncryptionKey<'a>>,
+    /// Initial sequence number to be used by the application
+    pub seq_number: Option<u32>,
+}
+
+/// Kerberos KRB-SAFE message
+///
+/// User data, protected by a keyed checksum.
+#[derive(Debug, PartialEq)]
+pub struct KrbSafe<'a> {
+    pub pvno: u32,
+    pub msg_type: MessageType,
+    pub safe_body: KrbSafeBody<'a>,
+    /// Checksum of the encoding of `safe_body`
+    pub cksum: Checksum<'a>,
+}
+
+/// Kerberos KRB-SAFE-BODY
+#[derive(Debug, PartialEq)]
+pub struct KrbSafeBody<'a> {
+    /// Application data
+    pub user_data: &'a [u8],
+    /// Current time on the sender's host
+    pub timestamp: Option<KerberosTime<'a>>,
+    /// Microsecond part of the timestamp
+    pub usec: Option<u32>,
+    /// Sequence number
+    pub seq_number: Option<u32>,
+    /// Address of the sender
+    pub s_address: HostAddress<'a>,
+    /// Address of the recipient
+    pub r_address: Option<HostAddress<'a>>,
+}
+
+/// Kerberos KRB-CRED message
+///
+/// Used to forward credentials (for ex. a delegated TGT) to a server.
+#[derive(Debug, PartialEq)]
+pub struct KrbCred<'a> {
+    pub pvno: u32,
+    pub msg_type: MessageType,
+    pub tickets: Vec<Ticket<'a>>,
+    /// Encrypted encoding of the EncKrbCredPart sequence
+    pub enc_part: EncryptedData<'a>,
+}
+
+impl<'a> KrbCred<'a> {
+    /// Get the server name and encryption type of each forwarded ticket
+    ///
+    /// The encryption type is the one of the ticket `enc_part` (that is, of the service key).
+    pub fn ticket_summaries(&self) -> Vec<(PrincipalName, EncryptionType)> {
+        self.tickets
+            .iter()
+            .map(|t| (t.sname.clone(), t.enc_part.etype))
+            .collect()
+    }
+}
+
+/// Kerberos EncKrbCredPart
+///
+/// This is the decrypted content of the `enc_part` field of a KRB-CRED.
+#[derive(Debug, PartialEq)]
+pub struct EncKrbCredPart<'a> {
+    /// Information on the tickets, in the same order as in the KRB-CRED
+    pub ticket_info: Vec<KrbCredInfo<'a>>,
+    pub nonce: Option<u32>,
+    /// Current time on the sender's host
+    pub timestamp: Option<KerberosTime<'a>>,
+    /// Microsecond part of the timestamp
+    pub usec: Option<u32>,
+    /// Address of the sender
+    pub s_address: Option<HostAddress<'a>>,
+    /// Address of the recipient
+    pub r_address: Option<HostAddress<'a>>,
+}
+
+/// Kerberos KrbCredInfo
+///
+/// Session key and (optional) information on one of the tickets of a KRB-CRED.
+#[derive(Debug, PartialEq)]
+pub struct KrbCredInfo<'a> {
+    /// Session key of the ticket
+    pub key: EncryptionKey<'a>,
+    /// Client realm
+    pub prealm: Option<Realm>,
+    /// Client name
+    pub pname: Option<PrincipalName>,
+    /// Ticket flags
+    pub flags: Option<DerObject<'a>>, // TicketFlags
+    pub authtime: Option<KerberosTime<'a>>,
+    pub starttime: Option<KerberosTime<'a>>,
+    pub endtime: Option<KerberosTime<'a>>,
+    pub renew_till: Option<KerberosTime<'a>>,
+    /// Server realm
+    pub srealm: Option<Realm>,
+    /// Server name
+    pub sname: Option<PrincipalName>,
+    /// Addresses from which the ticket can be used (empty if absent)
+    pub caddr: Vec<HostAddress<'a>>,
+}
+
+/// Kerberos ETYPE-INFO entry
+///
+/// Sent by the KDC in the legacy `PA-ETYPE-INFO` PA-Data. Unlike ETYPE-INFO2, the salt is an
+/// OCTET STRING and there are no string-to-key parameters.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ETypeInfoEntry<'a> {
+    /// Encryption type
+    pub etype: EncryptionType,
+    /// Salt, as sent by the KDC
+    pub salt: Option<&'a [u8]>,
+}
+
+/// Kerberos ETYPE-INFO2 entry
+///
+/// Sent by the KDC in the `PA-ETYPE-INFO2` PA-Data, to describe how the client key is derived.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ETypeInfo2Entry<'a> {
+    /// Encryption type
+    pub etype: EncryptionType,
+    /// Salt, decoded as a string (invalid UTF-8 sequences are replaced)
+    pub salt: Option<String>,
+    /// Exact bytes of the salt, as sent by the KDC
+    pub salt_bytes: Option<&'a [u8]>,
+    /// Parameters for the string-to-key function
+    pub s2kparams: Option<&'a [u8]>,
+}
+
+/// Compute the default salt of a principal, used when the KDC does not send a salt
+///
+/// The default salt is the realm, followed by the components of the principal name
+/// concatenated without separator (RFC4120 section 4). For ex. `host/www.example.com` in realm
+/// `EXAMPLE.COM` gives `EXAMPLE.COMhostwww.example.com`.
+pub fn default_salt(realm: &Realm, cname: &PrincipalName) -> String {
+    let mut salt = realm.0.clone();
+    for s in &cname.name_string {
+        salt.push_str(s);
+    }
+    salt
+}
+
+/// Error returned when the times of a ticket cannot be used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketTimeError {
+    /// `renew_till` is present, but the RENEWABLE flag is not set
+    RenewTillWithoutRenewable,
+    /// The time is not a valid KerberosTime
+    InvalidTime,
+}
+
+/// Lifetime of a ticket above which `EncTicketPart::suspicious_indicators` reports it
+///
+/// Active Directory limits the lifetime of tickets to 10 hours by default, while forging tools
+/// often use 10 years.
+pub const SUSPICIOUS_TICKET_LIFETIME_HOURS: i64 = 24;
+
+/// Age of `authtime` above which `EncTicketPart::suspicious_indicators` reports a ticket
+///
+/// Renewed tickets keep the `authtime` of the initial authentication, and Active Directory
+/// limits renewals to 7 days by default.
+pub const SUSPICIOUS_AUTHTIME_AGE_HOURS: i64 = 7 * 24;
+
+/// Sign of a forged ticket, returned by `EncTicketPart::suspicious_indicators`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketAnomaly {
+    /// The ticket is valid for more than `SUSPICIOUS_TICKET_LIFETIME_HOURS`
+    LongLifetime,
+    /// `authtime` is more than `SUSPICIOUS_AUTHTIME_AGE_HOURS` in the past
+    OldAuthtime,
+    /// The authorization data does not contain a PAC
+    MissingPac,
+    /// The flags are a combination that a KDC does not issue: reserved bit set, INVALID without
+    /// POSTDATED, POSTDATED without `starttime`, or `renew_till` without RENEWABLE
+    InconsistentFlags,
+}
+
+#[cfg(feature = "time")]
+fn renew_till_utc(
+    flags: TicketFlags,
+    renew_till: Option<KerberosTime>,
+) -> Result<Option<DateTime<Utc>>, TicketTimeError> {
+    let renew_till = match renew_till {
+        Some(t) => t,
+        None => return Ok(None),
+    };
+    if !flags.is_renewable() {
+        return Err(TicketTimeError::RenewTillWithoutRenewable);
+    }
+    renew_till
+        .to_datetime()
+        .map(Some)
+        .ok_or(TicketTimeError::InvalidTime)
+}
+
+#[cfg(feature = "time")]
+fn is_postdated(
+    flags: TicketFlags,
+    starttime: Option<KerberosTime>,
+    now: DateTime<Utc>,
+) -> Option<bool> {
+    let starttime = starttime?.to_datetime()?;
+    Some(starttime > now || flags.is_postdated())
+}
+
+// Test if a service name is `krbtgt`, ignoring case (as KDCs do)
+pub(crate) fn is_krbtgt(service: &[u8]) -> bool {
+    service.eq_ignore_ascii_case(b"krbtgt")
+}
+
+// Test if bit `n` of a KerberosFlags object is set, bit 0 being the most significant bit
+fn kerberos_flag_is_set(flags: &DerObject, n: usize) -> bool {
+    match flags.content {
+        BerObjectContent::BitString(_, ref b) => b
+            .data
+            .get(n / 8)
+            .map(|byte| byte & (0x80 >> (n % 8)) != 0)
+            .unwrap_or(false),
+        _ => false,
+    }
+}
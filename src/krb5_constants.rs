@@ -79,45 +79,185 @@ impl EncryptionType {
     pub const RC4_PLAIN_EXP                : EncryptionType = EncryptionType(-141);
 }
 
+static ALL_ENCRYPTION_TYPES: &[EncryptionType] = &[
+    EncryptionType::DES_CBC_CRC,
+    EncryptionType::DES_CBC_MD4,
+    EncryptionType::DES_CBC_MD5,
+    EncryptionType::DES3_CBC_MD5,
+    EncryptionType::DES3_CBC_SHA1,
+    EncryptionType::DSAWITHSHA1_CMSOID,
+    EncryptionType::MD5WITHRSAENCRYPTION_CMSOID,
+    EncryptionType::SHA1WITHRSAENCRYPTION_CMSOID,
+    EncryptionType::RC2CBC_ENVOID,
+    EncryptionType::RSAENCRYPTION_ENVOID,
+    EncryptionType::RSAES_OAEP_ENV_OID,
+    EncryptionType::DES_EDE3_CBC_ENV_OID,
+    EncryptionType::DES3_CBC_SHA1_KD,
+    EncryptionType::AES128_CTS_HMAC_SHA1_96,
+    EncryptionType::AES256_CTS_HMAC_SHA1_96,
+    EncryptionType::AES128_CTS_HMAC_SHA256_128,
+    EncryptionType::AES256_CTS_HMAC_SHA384_192,
+    EncryptionType::RC4_HMAC,
+    EncryptionType::RC4_HMAC_EXP,
+    EncryptionType::CAMELLIA128_CTS_CMAC,
+    EncryptionType::CAMELLIA256_CTS_CMAC,
+    EncryptionType::SUBKEY_KEYMATERIAL,
+    EncryptionType::RC4_MD4,
+    EncryptionType::RC4_PLAIN2,
+    EncryptionType::RC4_LM,
+    EncryptionType::RC4_SHA,
+    EncryptionType::DES_PLAIN,
+    EncryptionType::RC4_HMAC_OLD,
+    EncryptionType::RC4_PLAIN_OLD,
+    EncryptionType::RC4_HMAC_OLD_EXP,
+    EncryptionType::RC4_PLAIN_OLD_EXP,
+    EncryptionType::RC4_PLAIN,
+    EncryptionType::RC4_PLAIN_EXP,
+];
+
+impl EncryptionType {
+    /// All encryption types known by this crate
+    pub fn all_known() -> &'static [EncryptionType] {
+        ALL_ENCRYPTION_TYPES
+    }
+
+    /// Get the name of the encryption type (for ex. `aes256-cts-hmac-sha1-96`)
+    ///
+    /// Returns `None` if the encryption type is unknown.
+    #[rustfmt::skip]
+    pub fn name(&self) -> Option<&'static str> {
+        match self.0 {
+            1    => Some("des-cbc-crc"),
+            2    => Some("des-cbc-md4"),
+            3    => Some("des-cbc-md5"),
+            5    => Some("des3-cbc-md5"),
+            7    => Some("des3-cbc-sha1"),
+            9    => Some("dsaWithSHA1-CmsOID"),
+            10   => Some("md5WithRSAEncryption-CmsOID"),
+            11   => Some("sha1WithRSAEncryption-CmsOID"),
+            12   => Some("rc2CBC-EnvOID"),
+            13   => Some("rsaEncryption-EnvOID"),
+            14   => Some("rsaES-OAEP-ENV-OID"),
+            15   => Some("des-ede3-cbc-Env-OID"),
+            16   => Some("des3-cbc-sha1-kd"),
+            17   => Some("aes128-cts-hmac-sha1-96"),
+            18   => Some("aes256-cts-hmac-sha1-96"),
+            19   => Some("aes128-cts-hmac-sha256-128"),
+            20   => Some("aes256-cts-hmac-sha384-192"),
+            23   => Some("rc4-hmac"),
+            24   => Some("rc4-hmac-exp"),
+            25   => Some("camellia128-cts-cmac"),
+            26   => Some("camellia256-cts-cmac"),
+            65   => Some("subkey-keymaterial"),
+            // negative values
+            -128 => Some("rc4-md4"),
+            -129 => Some("rc4-plain2"),
+            -130 => Some("rc4-lm"),
+            -131 => Some("rc4-sha"),
+            -132 => Some("des-plain"),
+            -133 => Some("rc4-hmac-OLD"),
+            -134 => Some("rc4-plain-OLD"),
+            -135 => Some("rc4-hmac-OLD-exp"),
+            -136 => Some("rc4-plain-OLD-exp"),
+            -140 => Some("rc4-plain"),
+            -141 => Some("rc4-plain-exp"),
+            _    => None,
+        }
+    }
+
+    /// Relative strength of the encryption type, used to compare encryption types
+    ///
+    /// Higher is stronger. Unknown and non-Kerberos encryption types have strength 0.
+    pub(crate) fn strength(&self) -> u8 {
+        match *self {
+            EncryptionType::AES256_CTS_HMAC_SHA384_192 => 6,
+            EncryptionType::AES128_CTS_HMAC_SHA256_128 => 5,
+            EncryptionType::AES256_CTS_HMAC_SHA1_96 | EncryptionType::CAMELLIA256_CTS_CMAC => 4,
+            EncryptionType::AES128_CTS_HMAC_SHA1_96 | EncryptionType::CAMELLIA128_CTS_CMAC => 3,
+            EncryptionType::DES3_CBC_SHA1_KD
+            | EncryptionType::DES3_CBC_SHA1
+            | EncryptionType::DES3_CBC_MD5 => 2,
+            EncryptionType::RC4_HMAC | EncryptionType::RC4_HMAC_OLD => 1,
+            _ => 0,
+        }
+    }
+
+    /// Get the encryption type from its name (as returned by `name()`), ignoring case
+    pub fn from_name(s: &str) -> Option<EncryptionType> {
+        ALL_ENCRYPTION_TYPES
+            .iter()
+            .find(|e| e.name().map(|n| n.eq_ignore_ascii_case(s)).unwrap_or(false))
+            .copied()
+    }
+}
+
 impl fmt::Debug for EncryptionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => f.debug_tuple("EncryptionType").field(&self.0).finish(),
+        }
+    }
+}
+
+/// Checksum type
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumType(pub i32);
+
+#[rustfmt::skip]
+impl ChecksumType {
+    pub const CRC32                  : ChecksumType = ChecksumType(1);
+    pub const RSA_MD4                : ChecksumType = ChecksumType(2);
+    pub const RSA_MD4_DES            : ChecksumType = ChecksumType(3);
+    pub const DES_MAC                : ChecksumType = ChecksumType(4);
+    pub const DES_MAC_K              : ChecksumType = ChecksumType(5);
+    pub const RSA_MD4_DES_K          : ChecksumType = ChecksumType(6);
+    pub const RSA_MD5                : ChecksumType = ChecksumType(7);
+    pub const RSA_MD5_DES            : ChecksumType = ChecksumType(8);
+    pub const RSA_MD5_DES3           : ChecksumType = ChecksumType(9);
+    pub const SHA1                   : ChecksumType = ChecksumType(10);
+    pub const HMAC_SHA1_DES3_KD      : ChecksumType = ChecksumType(12);
+    pub const HMAC_SHA1_DES3         : ChecksumType = ChecksumType(13);
+    pub const SHA1_UNKEYED           : ChecksumType = ChecksumType(14);
+    pub const HMAC_SHA1_96_AES128    : ChecksumType = ChecksumType(15);
+    pub const HMAC_SHA1_96_AES256    : ChecksumType = ChecksumType(16);
+    pub const CMAC_CAMELLIA128       : ChecksumType = ChecksumType(17);
+    pub const CMAC_CAMELLIA256       : ChecksumType = ChecksumType(18);
+    pub const HMAC_SHA256_128_AES128 : ChecksumType = ChecksumType(19);
+    pub const HMAC_SHA384_192_AES256 : ChecksumType = ChecksumType(20);
+    /// GSS-API checksum (RFC4121)
+    pub const GSSAPI                 : ChecksumType = ChecksumType(0x8003);
+    // negative values
+    pub const HMAC_MD5               : ChecksumType = ChecksumType(-138);
+}
+
+impl fmt::Debug for ChecksumType {
     #[rustfmt::skip]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.0 {
-            1    => f.write_str("des-cbc-crc"),
-            2    => f.write_str("des-cbc-md4"),
-            3    => f.write_str("des-cbc-md5"),
-            5    => f.write_str("des3-cbc-md5"),
-            7    => f.write_str("des3-cbc-sha1"),
-            9    => f.write_str("dsaWithSHA1-CmsOID"),
-            10   => f.write_str("md5WithRSAEncryption-CmsOID"),
-            11   => f.write_str("sha1WithRSAEncryption-CmsOID"),
-            12   => f.write_str("rc2CBC-EnvOID"),
-            13   => f.write_str("rsaEncryption-EnvOID"),
-            14   => f.write_str("rsaES-OAEP-ENV-OID"),
-            15   => f.write_str("des-ede3-cbc-Env-OID"),
-            16   => f.write_str("des3-cbc-sha1-kd"),
-            17   => f.write_str("aes128-cts-hmac-sha1-96"),
-            18   => f.write_str("aes256-cts-hmac-sha1-96"),
-            19   => f.write_str("aes128-cts-hmac-sha256-128"),
-            20   => f.write_str("aes256-cts-hmac-sha384-192"),
-            23   => f.write_str("rc4-hmac"),
-            24   => f.write_str("rc4-hmac-exp"),
-            25   => f.write_str("camellia128-cts-cmac"),
-            26   => f.write_str("camellia256-cts-cmac"),
-            65   => f.write_str("subkey-keymaterial"),
+            1      => f.write_str("CRC32"),
+            2      => f.write_str("rsa-md4"),
+            3      => f.write_str("rsa-md4-des"),
+            4      => f.write_str("des-mac"),
+            5      => f.write_str("des-mac-k"),
+            6      => f.write_str("rsa-md4-des-k"),
+            7      => f.write_str("rsa-md5"),
+            8      => f.write_str("rsa-md5-des"),
+            9      => f.write_str("rsa-md5-des3"),
+            10     => f.write_str("sha1"),
+            12     => f.write_str("hmac-sha1-des3-kd"),
+            13     => f.write_str("hmac-sha1-des3"),
+            14     => f.write_str("sha1-unkeyed"),
+            15     => f.write_str("hmac-sha1-96-aes128"),
+            16     => f.write_str("hmac-sha1-96-aes256"),
+            17     => f.write_str("cmac-camellia128"),
+            18     => f.write_str("cmac-camellia256"),
+            19     => f.write_str("hmac-sha256-128-aes128"),
+            20     => f.write_str("hmac-sha384-192-aes256"),
+            0x8003 => f.write_str("gssapi"),
             // negative values
-            -128 => f.write_str("rc4-md4"),
-            -129 => f.write_str("rc4-plain2"),
-            -130 => f.write_str("rc4-lm"),
-            -131 => f.write_str("rc4-sha"),
-            -132 => f.write_str("des-plain"),
-            -133 => f.write_str("rc4-hmac-OLD"),
-            -134 => f.write_str("rc4-plain-OLD"),
-            -135 => f.write_str("rc4-hmac-OLD-exp"),
-            -136 => f.write_str("rc4-plain-OLD-exp"),
-            -140 => f.write_str("rc4-plain"),
-            -141 => f.write_str("rc4-plain-exp"),
-            n    => f.debug_tuple("EncryptionType").field(&n).finish(),
+            -138   => f.write_str("hmac-md5"),
+            n      => f.debug_tuple("ChecksumType").field(&n).finish(),
         }
     }
 }
@@ -154,6 +294,27 @@ impl MessageType {
     pub const KRB_ERROR      : MessageType = MessageType(30);
 }
 
+impl MessageType {
+    /// Test if this is a request (KRB_AS_REQ, KRB_TGS_REQ or KRB_AP_REQ)
+    pub fn is_request(&self) -> bool {
+        matches!(
+            *self,
+            MessageType::KRB_AS_REQ | MessageType::KRB_TGS_REQ | MessageType::KRB_AP_REQ
+        )
+    }
+
+    /// Test if this is a response (KRB_AS_REP, KRB_TGS_REP, KRB_AP_REP or KRB_ERROR)
+    pub fn is_response(&self) -> bool {
+        matches!(
+            *self,
+            MessageType::KRB_AS_REP
+                | MessageType::KRB_TGS_REP
+                | MessageType::KRB_AP_REP
+                | MessageType::KRB_ERROR
+        )
+    }
+}
+
 impl fmt::Debug for MessageType {
     #[rustfmt::skip]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -176,7 +337,9 @@ impl fmt::Debug for MessageType {
 }
 
 /// Name type
-#[derive(Clone, Copy, PartialEq, Eq)]
+///
+/// The default value is `KRB_NT_UNKNOWN`.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
 pub struct NameType(pub i32);
 
 #[rustfmt::skip]
@@ -226,19 +389,23 @@ pub struct PAType(pub i32);
 #[rustfmt::skip]
 impl PAType {
     /// DER encoding of AP-REQ
-    pub const PA_TGS_REQ          : PAType = PAType(1);
+    pub const PA_TGS_REQ             : PAType = PAType(1);
     /// DER encoding of PA-ENC-TIMESTAMP
-    pub const PA_ENC_TS           : PAType = PAType(2);
+    pub const PA_ENC_TS              : PAType = PAType(2);
     /// salt (not ASN.1 encoded)
-    pub const PA_PW_SALT          : PAType = PAType(3);
+    pub const PA_PW_SALT             : PAType = PAType(3);
     /// DER encoding of ETYPE-INFO
-    pub const PA_ETYPE_INFO       : PAType = PAType(11);
+    pub const PA_ETYPE_INFO          : PAType = PAType(11);
     /// DER encoding of ETYPE-INFO2
-    pub const PA_ETYPE_INFO2      : PAType = PAType(19);
+    pub const PA_ETYPE_INFO2         : PAType = PAType(19);
     /// Windows PAC request
-    pub const PA_PAC_REQUEST      : PAType = PAType(128);
+    pub const PA_PAC_REQUEST         : PAType = PAType(128);
+    /// FAST encrypted challenge (RFC6113)
+    pub const PA_ENCRYPTED_CHALLENGE : PAType = PAType(138);
     /// Support for FAST pre-auth mechanism
-    pub const PA_REQ_ENC_PA_REP   : PAType = PAType(149);
+    pub const PA_REQ_ENC_PA_REP      : PAType = PAType(149);
+    /// Windows PAC options ([MS-KILE] 2.2.10)
+    pub const PA_PAC_OPTIONS         : PAType = PAType(167);
 }
 
 impl fmt::Debug for PAType {
@@ -251,8 +418,137 @@ impl fmt::Debug for PAType {
             11  => f.write_str("pa-etype-info"),
             19  => f.write_str("pa-etype-info2"),
             128 => f.write_str("pa-pac-request"),
+            138 => f.write_str("pa-encrypted-challenge"),
             149 => f.write_str("pa-req-enc-pa-rep"),
+            167 => f.write_str("pa-pac-options"),
             n   => f.debug_tuple("PAType").field(&n).finish(),
         }
     }
 }
+
+/// Authorization data type
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct AdType(pub i32);
+
+#[rustfmt::skip]
+impl AdType {
+    /// Elements that can be ignored if not understood
+    pub const AD_IF_RELEVANT                    : AdType = AdType(1);
+    pub const AD_INTENDED_FOR_SERVER            : AdType = AdType(2);
+    pub const AD_INTENDED_FOR_APPLICATION_CLASS : AdType = AdType(3);
+    /// Elements issued and authenticated by the KDC
+    pub const AD_KDC_ISSUED                     : AdType = AdType(4);
+    /// Elements of which a given number must be understood
+    pub const AD_AND_OR                         : AdType = AdType(5);
+    pub const AD_MANDATORY_TICKET_EXTENSIONS    : AdType = AdType(6);
+    pub const AD_IN_TICKET_EXTENSIONS           : AdType = AdType(7);
+    /// Elements that must be understood by the KDC
+    pub const AD_MANDATORY_FOR_KDC              : AdType = AdType(8);
+    pub const OSF_DCE                           : AdType = AdType(64);
+    pub const SESAME                            : AdType = AdType(65);
+    pub const AD_OSF_DCE_PKI_CERTID             : AdType = AdType(66);
+    pub const AD_AUTHENTICATION_STRENGTH        : AdType = AdType(70);
+    pub const AD_FX_FAST_ARMOR                  : AdType = AdType(71);
+    pub const AD_FX_FAST_USED                   : AdType = AdType(72);
+    /// Windows PAC ([MS-PAC])
+    pub const AD_WIN2K_PAC                      : AdType = AdType(128);
+    pub const AD_ETYPE_NEGOTIATION              : AdType = AdType(129);
+    /// Windows token restrictions ([MS-KILE] 2.2.6)
+    pub const KERB_AUTH_DATA_TOKEN_RESTRICTIONS : AdType = AdType(141);
+    pub const KERB_LOCAL                        : AdType = AdType(142);
+    pub const AD_AUTH_DATA_AP_OPTIONS           : AdType = AdType(143);
+    pub const KERB_AUTH_DATA_TARGET_NAME        : AdType = AdType(144);
+}
+
+impl fmt::Debug for AdType {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            1   => f.write_str("AD-IF-RELEVANT"),
+            2   => f.write_str("AD-INTENDED-FOR-SERVER"),
+            3   => f.write_str("AD-INTENDED-FOR-APPLICATION-CLASS"),
+            4   => f.write_str("AD-KDCIssued"),
+            5   => f.write_str("AD-AND-OR"),
+            6   => f.write_str("AD-MANDATORY-TICKET-EXTENSIONS"),
+            7   => f.write_str("AD-IN-TICKET-EXTENSIONS"),
+            8   => f.write_str("AD-MANDATORY-FOR-KDC"),
+            64  => f.write_str("OSF-DCE"),
+            65  => f.write_str("SESAME"),
+            66  => f.write_str("AD-OSF-DCE-PKI-CERTID"),
+            70  => f.write_str("AD-authentication-strength"),
+            71  => f.write_str("AD-fx-fast-armor"),
+            72  => f.write_str("AD-fx-fast-used"),
+            128 => f.write_str("AD-WIN2K-PAC"),
+            129 => f.write_str("AD-ETYPE-NEGOTIATION"),
+            141 => f.write_str("KERB-AUTH-DATA-TOKEN-RESTRICTIONS"),
+            142 => f.write_str("KERB-LOCAL"),
+            143 => f.write_str("AD-AUTH-DATA-AP-OPTIONS"),
+            144 => f.write_str("KERB-AUTH-DATA-TARGET-NAME"),
+            n   => f.debug_tuple("AdType").field(&n).finish(),
+        }
+    }
+}
+
+/// LastReq entry type
+///
+/// Positive values apply to all the servers of the realm, negative values only to the KDC that
+/// sent the reply.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct LastReqType(pub i32);
+
+#[rustfmt::skip]
+impl LastReqType {
+    /// No information
+    pub const NONE                     : LastReqType = LastReqType(0);
+    /// Time of the last initial request for a TGT
+    pub const LAST_INITIAL_TGT_REQUEST : LastReqType = LastReqType(1);
+    /// Time of the last initial request
+    pub const LAST_INITIAL_REQUEST     : LastReqType = LastReqType(2);
+    /// Time of issue of the newest TGT used
+    pub const NEWEST_TGT_ISSUE         : LastReqType = LastReqType(3);
+    /// Time of the last renewal
+    pub const LAST_RENEWAL             : LastReqType = LastReqType(4);
+    /// Time of the last request (of any type)
+    pub const LAST_REQUEST             : LastReqType = LastReqType(5);
+    /// Time when the password will expire
+    pub const PASSWORD_EXPIRATION      : LastReqType = LastReqType(6);
+    /// Time when the account will expire
+    pub const ACCOUNT_EXPIRATION       : LastReqType = LastReqType(7);
+}
+
+impl fmt::Debug for LastReqType {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            0 => f.write_str("none"),
+            1 => f.write_str("last-initial-tgt-request"),
+            2 => f.write_str("last-initial-request"),
+            3 => f.write_str("newest-tgt-issue"),
+            4 => f.write_str("last-renewal"),
+            5 => f.write_str("last-request"),
+            6 => f.write_str("password-expiration"),
+            7 => f.write_str("account-expiration"),
+            n => f.debug_tuple("LastReqType").field(&n).finish(),
+        }
+    }
+}
+
+/// Transited encoding type
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct TransitedType(pub i32);
+
+#[rustfmt::skip]
+impl TransitedType {
+    /// Domain and X.500 compression (RFC4120 section 3.3.3.2)
+    pub const DOMAIN_X500_COMPRESS : TransitedType = TransitedType(1);
+}
+
+impl fmt::Debug for TransitedType {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            1 => f.write_str("DOMAIN-X500-COMPRESS"),
+            n => f.debug_tuple("TransitedType").field(&n).finish(),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Kerberos 5 encoding functions
+//!
+//! Only the small subset of DER needed by Kerberos structures is implemented here.
+
+use crate::krb5::*;
+
+fn der_length(len: usize, out: &mut Vec<u8>) {
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let skip = bytes.iter().take_while(|&&b| b == 0).count();
+        out.push(0x80 | (bytes.len() - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+}
+
+/// Encode a DER object with a single-byte tag
+pub(crate) fn der_tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(content.len() + 6);
+    out.push(tag);
+    der_length(content.len(), &mut out);
+    out.extend_from_slice(content);
+    out
+}
+
+/// Encode a SEQUENCE from the concatenation of its (already encoded) items
+pub(crate) fn der_sequence(items: &[Vec<u8>]) -> Vec<u8> {
+    der_tlv(0x30, &items.concat())
+}
+
+/// Encode an explicit context-specific tag around an (already encoded) object
+pub(crate) fn der_explicit(tag: u8, content: &[u8]) -> Vec<u8> {
+    der_tlv(0xa0 | tag, content)
+}
+
+/// Encode an INTEGER using the minimal number of bytes
+pub(crate) fn der_int32(v: i32) -> Vec<u8> {
+    let bytes = v.to_be_bytes();
+    let mut start = 0;
+    while start < 3 {
+        let (b, next) = (bytes[start], bytes[start + 1]);
+        if (b == 0 && next & 0x80 == 0) || (b == 0xff && next & 0x80 != 0) {
+            start += 1;
+        } else {
+            break;
+        }
+    }
+    der_tlv(0x02, &bytes[start..])
+}
+
+/// Encode a non-negative INTEGER using the minimal number of bytes
+pub(crate) fn der_u32(v: u32) -> Vec<u8> {
+    let bytes = u64::from(v).to_be_bytes();
+    // keep a leading zero byte if the high bit of the next one is set
+    let mut start = 3;
+    while start < 7 && bytes[start] == 0 && bytes[start + 1] & 0x80 == 0 {
+        start += 1;
+    }
+    der_tlv(0x02, &bytes[start..])
+}
+
+pub(crate) fn der_octetstring(v: &[u8]) -> Vec<u8> {
+    der_tlv(0x04, v)
+}
+
+pub(crate) fn der_generalstring(s: &str) -> Vec<u8> {
+    der_tlv(0x1b, s.as_bytes())
+}
+
+impl Realm {
+    /// Encode the realm to DER
+    pub fn to_der(&self) -> Vec<u8> {
+        der_generalstring(&self.0)
+    }
+}
+
+impl PrincipalName {
+    /// Encode the principal name to DER
+    pub fn to_der(&self) -> Vec<u8> {
+        let names: Vec<_> = self
+            .name_string
+            .iter()
+            .map(|s| der_generalstring(s))
+            .collect();
+        der_sequence(&[
+            der_explicit(0, &der_int32(self.name_type.0)),
+            der_explicit(1, &der_sequence(&names)),
+        ])
+    }
+}
+
+impl<'a> EncryptedData<'a> {
+    /// Encode the encrypted data to DER
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut items = vec![der_explicit(0, &der_int32(self.etype.0))];
+        if let Some(kvno) = self.kvno {
+            items.push(der_explicit(1, &der_u32(kvno)));
+        }
+        items.push(der_explicit(2, &der_octetstring(&self.cipher)));
+        der_sequence(&items)
+    }
+}
+
+/// Builder for the DER encoding of a Kerberos Ticket
+///
+/// This is mostly useful to create test inputs. The ticket version defaults to 5.
+///
+/// <pre>
+/// Ticket          ::= [APPLICATION 1] SEQUENCE {
+///         tkt-vno         [0] INTEGER (5),
+///         realm           [1] Realm,
+///         sname           [2] PrincipalName,
+///         enc-part        [3] EncryptedData -- EncTicketPart
+/// }
+/// </pre>
+#[derive(Debug, Clone)]
+pub struct TicketBuilder<'a> {
+    tkt_vno: u32,
+    realm: Realm,
+    sname: PrincipalName,
+    enc_part: EncryptedData<'a>,
+}
+
+impl<'a> TicketBuilder<'a> {
+    /// Create a builder for a Ticket issued by `realm` for the service `sname`
+    ///
+    /// `enc_part` is the encrypted EncTicketPart: the caller must encrypt it beforehand, since
+    /// this crate does not implement cryptography. All the fields of the ticket are set by this
+    /// function, so `build()` can be called directly. The `tkt-vno` is 5 by default, and can be
+    /// changed with `tkt_vno()`.
+    pub fn new(realm: Realm, sname: PrincipalName, enc_part: EncryptedData<'a>) -> Self {
+        TicketBuilder {
+            tkt_vno: 5,
+            realm,
+            sname,
+            enc_part,
+        }
+    }
+
+    /// Set the ticket version number
+    pub fn tkt_vno(mut self, tkt_vno: u32) -> Self {
+        self.tkt_vno = tkt_vno;
+        self
+    }
+
+    /// Encode the ticket to DER
+    pub fn build(&self) -> Vec<u8> {
+        let seq = der_sequence(&[
+            der_explicit(0, &der_u32(self.tkt_vno)),
+            der_explicit(1, &self.realm.to_der()),
+            der_explicit(2, &self.sname.to_der()),
+            der_explicit(3, &self.enc_part.to_der()),
+        ]);
+        der_tlv(0x61, &seq)
+    }
+}
+
+impl<'a> PAData<'a> {
+    /// Encode the PA-DATA to DER
+    ///
+    /// <pre>
+    /// PA-DATA         ::= SEQUENCE {
+    ///         -- NOTE: first tag is [1], not [0]
+    ///         padata-type     [1] Int32,
+    ///         padata-value    [2] OCTET STRING -- might be encoded AP-REQ
+    /// }
+    /// </pre>
+    pub fn to_der(&self) -> Vec<u8> {
+        der_sequence(&[
+            der_explicit(1, &der_int32(self.padata_type.0)),
+            der_explicit(2, &der_octetstring(self.padata_value)),
+        ])
+    }
+}
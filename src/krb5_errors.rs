@@ -151,3 +151,32 @@ impl fmt::Debug for ErrorCode {
         }
     }
 }
+
+/// Account state, as reported by a Kerberos error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountState {
+    /// Client credentials have been revoked (account disabled, locked or expired)
+    Revoked,
+    /// Account has expired
+    Expired,
+    /// Account is locked out
+    Locked,
+    /// Password has expired
+    PasswordExpired,
+    /// Account is disabled
+    Disabled,
+}
+
+impl ErrorCode {
+    /// Get the account state indicated by this error code, if any
+    ///
+    /// `KDC_ERR_CLIENT_REVOKED` is used for disabled, locked and expired accounts. Windows KDCs
+    /// send the exact reason in the extended error (see `KrbError::account_state_hint`).
+    pub fn account_state_hint(&self) -> Option<AccountState> {
+        match *self {
+            ErrorCode::KDC_ERR_CLIENT_REVOKED => Some(AccountState::Revoked),
+            ErrorCode::KDC_ERR_KEY_EXPIRED => Some(AccountState::PasswordExpired),
+            _ => None,
+        }
+    }
+}
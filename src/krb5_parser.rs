@@ -6,11 +6,41 @@ use der_parser::error::*;
 use nom::combinator::{complete, map, map_res, opt, verify};
 use nom::error::{make_error, ErrorKind};
 use nom::multi::many1;
+use nom::number::complete::le_u32;
 use nom::{Err, IResult};
 use std::str;
 use std::borrow::Cow;
 use crate::krb5::*;
 
+/// Apply a parser, and fail with `InvalidLength` if it did not consume all input
+///
+/// This is used on the content of the outer application tag of messages, to detect
+/// messages where the declared length does not match the length of the encoded content.
+fn all_consumed<'a, O, F>(mut f: F) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], O, BerError>
+where
+    F: FnMut(&'a [u8]) -> IResult<&'a [u8], O, BerError>,
+{
+    move |i| {
+        let (rem, o) = f(i)?;
+        if !rem.is_empty() {
+            return Err(Err::Error(BerError::InvalidLength));
+        }
+        Ok((rem, o))
+    }
+}
+
+/// Check that the header of a message is an application-class tag
+///
+/// This only rejects context-specific or private tags having the expected tag number, with
+/// `InvalidTag`. Primitive encodings (`ConstructExpected`) and universal tags (`InvalidClass`)
+/// are already rejected by der-parser, before this check runs.
+fn check_application_header(hdr: &BerObjectHeader) -> Result<(), Err<BerError>> {
+    if !hdr.is_application() {
+        return Err(Err::Error(BerError::InvalidTag));
+    }
+    Ok(())
+}
+
 /// Parse a signed 32 bits integer
 ///
 /// <pre>
@@ -60,8 +90,60 @@ pub fn parse_kerberos_string(i: &[u8]) -> IResult<&[u8], String, BerError> {
     }
 }
 
+// KerberosString, as the string type and the raw content bytes. The same string types as
+// `parse_kerberos_string_lenient` are accepted.
+fn parse_kerberos_string_bytes(i: &[u8]) -> IResult<&[u8], (BerTag, &[u8]), BerError> {
+    let (rem, obj) = parse_der(i)?;
+    if obj.header.class != BerClass::Universal {
+        return Err(Err::Error(make_error(i, ErrorKind::Tag)));
+    }
+    match obj.header.tag {
+        BerTag::GeneralString | BerTag::Utf8String | BerTag::Ia5String | BerTag::BmpString => {
+            let s = obj.as_slice().map_err(Err::Error)?;
+            Ok((rem, (obj.header.tag, s)))
+        }
+        _ => Err(Err::Error(make_error(i, ErrorKind::Tag))),
+    }
+}
+
+/// Parse a KerberosString, also accepting UTF8String, IA5String and BMPString encodings
+///
+/// KerberosString is defined as a GeneralString, but some implementations encode
+/// realms and principal names using other string types. BMPString values are
+/// decoded as UTF-16 (big-endian).
+pub fn parse_kerberos_string_lenient(i: &[u8]) -> IResult<&[u8], String, BerError> {
+    let (rem, obj) = parse_der(i)?;
+    if obj.header.class != BerClass::Universal {
+        return Err(Err::Error(make_error(i, ErrorKind::Tag)));
+    }
+    let s = match obj.header.tag {
+        BerTag::GeneralString | BerTag::Utf8String | BerTag::Ia5String => obj
+            .as_slice()
+            .ok()
+            .and_then(|s| str::from_utf8(s).ok())
+            .map(|s| s.to_owned()),
+        BerTag::BmpString => obj.as_slice().ok().and_then(decode_bmp_string),
+        _ => return Err(Err::Error(make_error(i, ErrorKind::Tag))),
+    };
+    match s {
+        Some(s) => Ok((rem, s)),
+        None => Err(Err::Error(make_error(i, ErrorKind::IsNot))),
+    }
+}
+
+fn decode_bmp_string(s: &[u8]) -> Option<String> {
+    let chunks = s.chunks_exact(2);
+    if !chunks.remainder().is_empty() {
+        return None;
+    }
+    let v: Vec<u16> = chunks
+        .map(|c| (u16::from(c[0]) << 8) | u16::from(c[1]))
+        .collect();
+    String::from_utf16(&v).ok()
+}
+
 fn parse_kerberos_string_sequence(i: &[u8]) -> IResult<&[u8], Vec<String>, BerError> {
-    parse_ber_sequence_of_v(parse_kerberos_string)(i)
+    parse_ber_sequence_of_v(parse_kerberos_string_lenient)(i)
 }
 
 /// Parse Kerberos flags
@@ -81,9 +163,11 @@ pub fn parse_kerberos_flags(i: &[u8]) -> IResult<&[u8], DerObject, BerError> {
 /// <pre>
 /// Realm           ::= KerberosString
 /// </pre>
+///
+/// See `parse_kerberos_string_lenient` for the accepted string encodings.
 #[inline]
 pub fn parse_krb5_realm(i: &[u8]) -> IResult<&[u8], Realm, BerError> {
-    map(parse_kerberos_string, Realm)(i)
+    map(parse_kerberos_string_lenient, Realm)(i)
 }
 
 /// Parse Kerberos PrincipalName
@@ -112,12 +196,23 @@ pub fn parse_krb5_principalname(i: &[u8]) -> IResult<&[u8], PrincipalName, BerEr
 
 /// Parse of a Kerberos Time
 ///
+/// Only the `YYYYMMDDHHMMSSZ` form is accepted, other values are rejected with `BerValueError`.
+///
 /// <pre>
 /// KerberosTime    ::= GeneralizedTime -- with no fractional seconds
 /// </pre>
-#[inline]
-pub fn parse_kerberos_time(i: &[u8]) -> IResult<&[u8], DerObject, BerError> {
-    parse_der_generalizedtime(i)
+pub fn parse_kerberos_time(i: &[u8]) -> IResult<&[u8], KerberosTime, BerError> {
+    map_res(parse_der_generalizedtime, |x: DerObject| match x.content {
+        BerObjectContent::GeneralizedTime(s) => {
+            let b = s.as_bytes();
+            if b.len() == 15 && b[..14].iter().all(u8::is_ascii_digit) && b[14] == b'Z' {
+                Ok(KerberosTime(b))
+            } else {
+                Err(BerError::BerValueError)
+            }
+        }
+        _ => Err(BerError::BerTypeError),
+    })(i)
 }
 
 /// Parse Kerberos HostAddress
@@ -154,6 +249,8 @@ pub fn parse_krb5_hostaddresses(i: &[u8]) -> IResult<&[u8], Vec<HostAddress>, Be
 
 /// Parse Kerberos Ticket
 ///
+/// A `tkt-vno` other than 5 is rejected with `BerError::Custom(5)`.
+///
 /// <pre>
 /// Ticket          ::= [APPLICATION 1] SEQUENCE {
 ///         tkt-vno         [0] INTEGER (5),
@@ -164,10 +261,8 @@ pub fn parse_krb5_hostaddresses(i: &[u8]) -> IResult<&[u8], Vec<HostAddress>, Be
 /// </pre>
 pub fn parse_krb5_ticket<'a>(i: &'a [u8]) -> IResult<&'a [u8], Ticket<'a>, BerError> {
     parse_ber_tagged_explicit_g(BerTag(1), |i, hdr| {
-        if !hdr.is_application() {
-            return Err(Err::Error(BerError::InvalidTag));
-        }
-        parse_ber_sequence_defined_g(|i, _| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_ber_sequence_defined_g(|i, _| {
             let (i, tkt_vno) = parse_ber_tagged_explicit_g(0, |a, _| parse_der_u32(a))(i)?;
             if tkt_vno != 5 {
                 return Err(Err::Error(BerError::Custom(5)));
@@ -182,7 +277,90 @@ pub fn parse_krb5_ticket<'a>(i: &'a [u8]) -> IResult<&'a [u8], Ticket<'a>, BerEr
                 enc_part: Cow::Owned(enc_part),
             };
             Ok((i, tkt))
-        })(i)
+        }))(i)
+    })(i)
+}
+
+/// Parse a Kerberos TransitedEncoding
+///
+/// <pre>
+/// TransitedEncoding       ::= SEQUENCE {
+///         tr-type         [0] Int32 -- must be registered --,
+///         contents        [1] OCTET STRING
+/// }
+/// </pre>
+pub fn parse_transited_encoding<'a>(
+    i: &'a [u8],
+) -> IResult<&'a [u8], TransitedEncoding<'a>, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, tr_type) =
+            parse_ber_tagged_explicit_g(0, |a, _| map(parse_der_int32, TransitedType)(a))(i)?;
+        let (i, contents) = parse_ber_tagged_explicit_g(1, |a, _| {
+            map_res(parse_ber_octetstring, |o| o.as_slice())(a)
+        })(i)?;
+        let tr = TransitedEncoding { tr_type, contents };
+        Ok((i, tr))
+    })(i)
+}
+
+/// Parse a Kerberos EncTicketPart
+///
+/// <pre>
+/// EncTicketPart   ::= [APPLICATION 3] SEQUENCE {
+///         flags                   [0] TicketFlags,
+///         key                     [1] EncryptionKey,
+///         crealm                  [2] Realm,
+///         cname                   [3] PrincipalName,
+///         transited               [4] TransitedEncoding,
+///         authtime                [5] KerberosTime,
+///         starttime               [6] KerberosTime OPTIONAL,
+///         endtime                 [7] KerberosTime,
+///         renew-till              [8] KerberosTime OPTIONAL,
+///         caddr                   [9] HostAddresses OPTIONAL,
+///         authorization-data      [10] AuthorizationData OPTIONAL
+/// }
+/// </pre>
+pub fn parse_enc_ticket_part(i: &[u8]) -> IResult<&[u8], EncTicketPart, BerError> {
+    parse_ber_tagged_explicit_g(BerTag(3), |i, hdr| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_ber_sequence_defined_g(|i, _| {
+            let (i, flags) = parse_ber_tagged_explicit_g(0, |a, _| parse_kerberos_flags(a))(i)?;
+            let (i, key) = parse_ber_tagged_explicit_g(1, |a, _| parse_encryption_key(a))(i)?;
+            let (i, crealm) = parse_ber_tagged_explicit_g(2, |a, _| parse_krb5_realm(a))(i)?;
+            let (i, cname) = parse_ber_tagged_explicit_g(3, |a, _| parse_krb5_principalname(a))(i)?;
+            let (i, transited) =
+                parse_ber_tagged_explicit_g(4, |a, _| parse_transited_encoding(a))(i)?;
+            let (i, authtime) = parse_ber_tagged_explicit_g(5, |a, _| parse_kerberos_time(a))(i)?;
+            let (i, starttime) = opt(complete(parse_ber_tagged_explicit_g(6, |a, _| {
+                parse_kerberos_time(a)
+            })))(i)?;
+            let (i, endtime) = parse_ber_tagged_explicit_g(7, |a, _| parse_kerberos_time(a))(i)?;
+            let (i, renew_till) = opt(complete(parse_ber_tagged_explicit_g(8, |a, _| {
+                parse_kerberos_time(a)
+            })))(i)?;
+            let (i, caddr) = opt(complete(parse_ber_tagged_explicit_g(9, |a, _| {
+                parse_krb5_hostaddresses(a)
+            })))(i)?;
+            let caddr = caddr.unwrap_or_default();
+            let (i, authorization_data) =
+                opt(complete(parse_ber_tagged_explicit_g(10, |a, _| {
+                    parse_authorization_data(a)
+                })))(i)?;
+            let part = EncTicketPart {
+                flags,
+                key,
+                crealm,
+                cname,
+                transited,
+                authtime,
+                starttime,
+                endtime,
+                renew_till,
+                caddr,
+                authorization_data,
+            };
+            Ok((i, part))
+        }))(i)
     })(i)
 }
 
@@ -207,14 +385,174 @@ pub fn parse_encrypted<'a>(i: &'a [u8]) -> IResult<&'a [u8], EncryptedData<'a>,
         let enc = EncryptedData {
             etype,
             kvno,
-            cipher: std::borrow::Cow::Borrowed(cipher),
+            cipher: Cow::Borrowed(cipher),
         };
         Ok((i, enc))
     })(i)
 }
 
+/// Parse a Kerberos Checksum
+///
+/// <pre>
+/// Checksum        ::= SEQUENCE {
+///         cksumtype       [0] Int32,
+///         checksum        [1] OCTET STRING
+/// }
+/// </pre>
+pub fn parse_checksum<'a>(i: &'a [u8]) -> IResult<&'a [u8], Checksum<'a>, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, cksumtype) =
+            parse_ber_tagged_explicit_g(0, |a, _| map(parse_der_int32, ChecksumType)(a))(i)?;
+        let (i, checksum) = parse_ber_tagged_explicit_g(1, |a, _| {
+            map_res(parse_ber_octetstring, |o| o.as_slice())(a)
+        })(i)?;
+        let cksum = Checksum {
+            cksumtype,
+            checksum,
+        };
+        Ok((i, cksum))
+    })(i)
+}
+
+/// Parse a Kerberos AuthorizationData element
+///
+/// <pre>
+/// AuthorizationData       ::= SEQUENCE OF SEQUENCE {
+///         ad-type         [0] Int32,
+///         ad-data         [1] OCTET STRING
+/// }
+/// </pre>
+pub fn parse_authorization_data_element<'a>(
+    i: &'a [u8],
+) -> IResult<&'a [u8], AuthorizationDataElement<'a>, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, ad_type) =
+            parse_ber_tagged_explicit_g(0, |a, _| map(parse_der_int32, AdType)(a))(i)?;
+        let (i, ad_data) = parse_ber_tagged_explicit_g(1, |a, _| {
+            map_res(parse_ber_octetstring, |o| o.as_slice())(a)
+        })(i)?;
+        let ad = AuthorizationDataElement { ad_type, ad_data };
+        Ok((i, ad))
+    })(i)
+}
+
+/// Parse Kerberos AuthorizationData
+///
+/// <pre>
+/// AuthorizationData       ::= SEQUENCE OF SEQUENCE {
+///         ad-type         [0] Int32,
+///         ad-data         [1] OCTET STRING
+/// }
+/// </pre>
+pub fn parse_authorization_data(i: &[u8]) -> IResult<&[u8], AuthorizationData, BerError> {
+    parse_ber_sequence_of_v(parse_authorization_data_element)(i)
+}
+
+/// Maximum nesting level of AD-IF-RELEVANT elements accepted by `parse_ad_if_relevant`
+pub const MAX_AD_IF_RELEVANT_DEPTH: usize = 8;
+
+/// Parse the content of an AD-IF-RELEVANT authorization data element
+///
+/// The nested AD-IF-RELEVANT elements are also checked, recursively. Inputs with more than
+/// `MAX_AD_IF_RELEVANT_DEPTH` nested levels are rejected with `BerMaxDepth`.
+///
+/// <pre>
+/// AD-IF-RELEVANT          ::= AuthorizationData
+/// </pre>
+pub fn parse_ad_if_relevant(i: &[u8]) -> IResult<&[u8], AuthorizationData, BerError> {
+    parse_ad_if_relevant_depth(i, MAX_AD_IF_RELEVANT_DEPTH)
+}
+
+fn parse_ad_if_relevant_depth(
+    i: &[u8],
+    depth: usize,
+) -> IResult<&[u8], AuthorizationData, BerError> {
+    if depth == 0 {
+        return Err(Err::Error(BerError::BerMaxDepth));
+    }
+    let (rem, ad) = parse_authorization_data(i)?;
+    for elt in &ad {
+        if elt.ad_type == AdType::AD_IF_RELEVANT {
+            parse_ad_if_relevant_depth(elt.ad_data, depth - 1)?;
+        }
+    }
+    Ok((rem, ad))
+}
+
+/// Parse the content of an AD-KDCIssued authorization data element
+///
+/// <pre>
+/// AD-KDCIssued            ::= SEQUENCE {
+///         ad-checksum     [0] Checksum,
+///         i-realm         [1] Realm OPTIONAL,
+///         i-sname         [2] PrincipalName OPTIONAL,
+///         elements        [3] AuthorizationData
+/// }
+/// </pre>
+pub fn parse_ad_kdc_issued(i: &[u8]) -> IResult<&[u8], AdKdcIssued, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, ad_checksum) = parse_ber_tagged_explicit_g(0, |a, _| parse_checksum(a))(i)?;
+        let (i, i_realm) = opt(complete(parse_ber_tagged_explicit_g(1, |a, _| {
+            parse_krb5_realm(a)
+        })))(i)?;
+        let (i, i_sname) = opt(complete(parse_ber_tagged_explicit_g(2, |a, _| {
+            parse_krb5_principalname(a)
+        })))(i)?;
+        let (i, elements) = parse_ber_tagged_explicit_g(3, |a, _| parse_authorization_data(a))(i)?;
+        let ad = AdKdcIssued {
+            ad_checksum,
+            i_realm,
+            i_sname,
+            elements,
+        };
+        Ok((i, ad))
+    })(i)
+}
+
+/// Parse the content of an AD-AND-OR authorization data element
+///
+/// <pre>
+/// AD-AND-OR               ::= SEQUENCE {
+///         condition-count [0] Int32,
+///         elements        [1] AuthorizationData
+/// }
+/// </pre>
+pub fn parse_ad_and_or(i: &[u8]) -> IResult<&[u8], AdAndOr, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, condition_count) = parse_ber_tagged_explicit_g(0, |a, _| parse_der_int32(a))(i)?;
+        let (i, elements) = parse_ber_tagged_explicit_g(1, |a, _| parse_authorization_data(a))(i)?;
+        let ad = AdAndOr {
+            condition_count,
+            elements,
+        };
+        Ok((i, ad))
+    })(i)
+}
+
+/// Parse Kerberos EncryptionKey
+///
+/// <pre>
+/// EncryptionKey   ::= SEQUENCE {
+///         keytype         [0] Int32 -- actually encryption type --,
+///         keyvalue        [1] OCTET STRING
+/// }
+/// </pre>
+pub fn parse_encryption_key<'a>(i: &'a [u8]) -> IResult<&'a [u8], EncryptionKey<'a>, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, keytype) =
+            parse_ber_tagged_explicit_g(0, |a, _| map(parse_der_int32, EncryptionType)(a))(i)?;
+        let (i, keyvalue) = parse_ber_tagged_explicit_g(1, |a, _| {
+            map_res(parse_ber_octetstring, |o| o.as_slice())(a)
+        })(i)?;
+        Ok((i, EncryptionKey { keytype, keyvalue }))
+    })(i)
+}
+
 /// Parse a Kerberos KDC Request
 ///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_AS_REQ` or `KRB_TGS_REQ` with `BerError::BerValueError`.
+///
 /// <pre>
 /// KDC-REQ         ::= SEQUENCE {
 ///         -- NOTE: first tag is [1], not [0]
@@ -228,10 +566,18 @@ pub fn parse_encrypted<'a>(i: &'a [u8]) -> IResult<&'a [u8], EncryptedData<'a>,
 pub fn parse_kdc_req(i: &[u8]) -> IResult<&[u8], KdcReq, BerError> {
     parse_ber_sequence_defined_g(|i, _| {
         let (i, pvno) = parse_ber_tagged_explicit_g(1, |a, _| parse_der_u32(a))(i)?;
+        if pvno != 5 {
+            return Err(Err::Error(BerError::Custom(5)));
+        }
         let (i, msg_type) =
             parse_ber_tagged_explicit_g(2, |a, _| map(parse_der_u32, MessageType)(a))(i)?;
-        let (i, padata) = parse_ber_tagged_explicit_g(3, |a, _| parse_krb5_padata_sequence(a))(i)
-            .unwrap_or_default();
+        if msg_type != MessageType::KRB_AS_REQ && msg_type != MessageType::KRB_TGS_REQ {
+            return Err(Err::Error(BerError::BerValueError));
+        }
+        let (i, padata) = opt(complete(parse_ber_tagged_explicit_g(3, |a, _| {
+            parse_krb5_padata_sequence(a)
+        })))(i)?;
+        let padata = padata.unwrap_or_default();
         let (i, req_body) = parse_ber_tagged_explicit_g(4, |a, _| parse_kdc_req_body(a))(i)?;
         let req = KdcReq {
             pvno,
@@ -322,34 +668,47 @@ pub fn parse_kdc_req_body(i: &[u8]) -> IResult<&[u8], KdcReqBody, BerError> {
 
 /// Parse a Kerberos AS Request
 ///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_AS_REQ` with `BerError::BerValueError`.
+///
 /// <pre>
 /// AS-REQ          ::= [APPLICATION 10] KDC-REQ
 /// </pre>
 pub fn parse_as_req(i: &[u8]) -> IResult<&[u8], KdcReq, BerError> {
     parse_ber_tagged_explicit_g(BerTag(10), |i, hdr| {
-        if !hdr.is_application() {
-            return Err(Err::Error(BerError::InvalidTag));
+        check_application_header(&hdr)?;
+        let (i, req) = all_consumed(parse_kdc_req)(i)?;
+        if req.msg_type != MessageType::KRB_AS_REQ {
+            return Err(Err::Error(BerError::BerValueError));
         }
-        parse_kdc_req(i)
+        Ok((i, req))
     })(i)
 }
 
 /// Parse a Kerberos TGS Request
 ///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_TGS_REQ` with `BerError::BerValueError`.
+///
 /// <pre>
 /// TGS-REQ          ::= [APPLICATION 12] KDC-REQ
 /// </pre>
 pub fn parse_tgs_req(i: &[u8]) -> IResult<&[u8], KdcReq, BerError> {
     parse_ber_tagged_explicit_g(BerTag(12), |i, hdr| {
-        if !hdr.is_application() {
-            return Err(Err::Error(BerError::InvalidTag));
+        check_application_header(&hdr)?;
+        let (i, req) = all_consumed(parse_kdc_req)(i)?;
+        if req.msg_type != MessageType::KRB_TGS_REQ {
+            return Err(Err::Error(BerError::BerValueError));
         }
-        parse_kdc_req(i)
+        Ok((i, req))
     })(i)
 }
 
 /// Parse a Kerberos KDC Reply
 ///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_AS_REP` or `KRB_TGS_REP` with `BerError::BerValueError`.
+///
 /// <pre>
 /// KDC-REP         ::= SEQUENCE {
 ///         pvno            [0] INTEGER (5),
@@ -367,10 +726,18 @@ pub fn parse_tgs_req(i: &[u8]) -> IResult<&[u8], KdcReq, BerError> {
 pub fn parse_kdc_rep(i: &[u8]) -> IResult<&[u8], KdcRep, BerError> {
     parse_ber_sequence_defined_g(|i, _| {
         let (i, pvno) = parse_ber_tagged_explicit_g(0, |a, _| parse_der_u32(a))(i)?;
+        if pvno != 5 {
+            return Err(Err::Error(BerError::Custom(5)));
+        }
         let (i, msg_type) =
             parse_ber_tagged_explicit_g(1, |a, _| map(parse_der_u32, MessageType)(a))(i)?;
-        let (i, padata) = parse_ber_tagged_explicit_g(2, |a, _| parse_krb5_padata_sequence(a))(i)
-            .unwrap_or_default();
+        if msg_type != MessageType::KRB_AS_REP && msg_type != MessageType::KRB_TGS_REP {
+            return Err(Err::Error(BerError::BerValueError));
+        }
+        let (i, padata) = opt(complete(parse_ber_tagged_explicit_g(2, |a, _| {
+            parse_krb5_padata_sequence(a)
+        })))(i)?;
+        let padata = padata.unwrap_or_default();
         let (i, crealm) = parse_ber_tagged_explicit_g(3, |a, _| parse_krb5_realm(a))(i)?;
         let (i, cname) = parse_ber_tagged_explicit_g(4, |a, _| parse_krb5_principalname(a))(i)?;
         let (i, ticket) = parse_ber_tagged_explicit_g(5, |a, _| parse_krb5_ticket(a))(i)?;
@@ -390,34 +757,160 @@ pub fn parse_kdc_rep(i: &[u8]) -> IResult<&[u8], KdcRep, BerError> {
 
 /// Parse a Kerberos AS Reply
 ///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_AS_REP` with `BerError::BerValueError`.
+///
 /// <pre>
 /// AS-REP          ::= [APPLICATION 11] KDC-REP
 /// </pre>
 pub fn parse_as_rep(i: &[u8]) -> IResult<&[u8], KdcRep, BerError> {
     parse_ber_tagged_explicit_g(BerTag(11), |i, hdr| {
-        if !hdr.is_application() {
-            return Err(Err::Error(BerError::InvalidTag));
+        check_application_header(&hdr)?;
+        let (i, rep) = all_consumed(parse_kdc_rep)(i)?;
+        if rep.msg_type != MessageType::KRB_AS_REP {
+            return Err(Err::Error(BerError::BerValueError));
         }
-        parse_kdc_rep(i)
+        Ok((i, rep))
     })(i)
 }
 
 /// Parse a Kerberos TGS Reply
 ///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_TGS_REP` with `BerError::BerValueError`.
+///
 /// <pre>
 /// TGS-REP          ::= [APPLICATION 13] KDC-REP
 /// </pre>
 pub fn parse_tgs_rep(i: &[u8]) -> IResult<&[u8], KdcRep, BerError> {
     parse_ber_tagged_explicit_g(BerTag(13), |i, hdr| {
-        if !hdr.is_application() {
-            return Err(Err::Error(BerError::InvalidTag));
+        check_application_header(&hdr)?;
+        let (i, rep) = all_consumed(parse_kdc_rep)(i)?;
+        if rep.msg_type != MessageType::KRB_TGS_REP {
+            return Err(Err::Error(BerError::BerValueError));
         }
-        parse_kdc_rep(i)
+        Ok((i, rep))
+    })(i)
+}
+
+/// Parse a Kerberos LastReq entry
+///
+/// <pre>
+/// LastReq         ::=     SEQUENCE OF SEQUENCE {
+///         lr-type         [0] Int32,
+///         lr-value        [1] KerberosTime
+/// }
+/// </pre>
+pub fn parse_last_req_entry<'a>(i: &'a [u8]) -> IResult<&'a [u8], LastReqEntry<'a>, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, lr_type) =
+            parse_ber_tagged_explicit_g(0, |a, _| map(parse_der_int32, LastReqType)(a))(i)?;
+        let (i, lr_value) = parse_ber_tagged_explicit_g(1, |a, _| parse_kerberos_time(a))(i)?;
+        let entry = LastReqEntry { lr_type, lr_value };
+        Ok((i, entry))
+    })(i)
+}
+
+/// Parse Kerberos LastReq
+///
+/// <pre>
+/// LastReq         ::=     SEQUENCE OF SEQUENCE {
+///         lr-type         [0] Int32,
+///         lr-value        [1] KerberosTime
+/// }
+/// </pre>
+pub fn parse_last_req(i: &[u8]) -> IResult<&[u8], LastReq, BerError> {
+    parse_ber_sequence_of_v(parse_last_req_entry)(i)
+}
+
+/// Parse the decrypted part of a Kerberos KDC Reply
+///
+/// <pre>
+/// EncKDCRepPart   ::= SEQUENCE {
+///         key             [0] EncryptionKey,
+///         last-req        [1] LastReq,
+///         nonce           [2] UInt32,
+///         key-expiration  [3] KerberosTime OPTIONAL,
+///         flags           [4] TicketFlags,
+///         authtime        [5] KerberosTime,
+///         starttime       [6] KerberosTime OPTIONAL,
+///         endtime         [7] KerberosTime,
+///         renew-till      [8] KerberosTime OPTIONAL,
+///         srealm          [9] Realm,
+///         sname           [10] PrincipalName,
+///         caddr           [11] HostAddresses OPTIONAL
+/// }
+/// </pre>
+pub fn parse_enc_kdc_rep_part(i: &[u8]) -> IResult<&[u8], EncKdcRepPart, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, key) = parse_ber_tagged_explicit_g(0, |a, _| parse_encryption_key(a))(i)?;
+        let (i, last_req) = parse_ber_tagged_explicit_g(1, |a, _| parse_last_req(a))(i)?;
+        let (i, nonce) = parse_ber_tagged_explicit_g(2, |a, _| parse_der_u32(a))(i)?;
+        let (i, key_expiration) = opt(complete(parse_ber_tagged_explicit_g(3, |a, _| {
+            parse_kerberos_time(a)
+        })))(i)?;
+        let (i, flags) = parse_ber_tagged_explicit_g(4, |a, _| parse_kerberos_flags(a))(i)?;
+        let (i, authtime) = parse_ber_tagged_explicit_g(5, |a, _| parse_kerberos_time(a))(i)?;
+        let (i, starttime) = opt(complete(parse_ber_tagged_explicit_g(6, |a, _| {
+            parse_kerberos_time(a)
+        })))(i)?;
+        let (i, endtime) = parse_ber_tagged_explicit_g(7, |a, _| parse_kerberos_time(a))(i)?;
+        let (i, renew_till) = opt(complete(parse_ber_tagged_explicit_g(8, |a, _| {
+            parse_kerberos_time(a)
+        })))(i)?;
+        let (i, srealm) = parse_ber_tagged_explicit_g(9, |a, _| parse_krb5_realm(a))(i)?;
+        let (i, sname) = parse_ber_tagged_explicit_g(10, |a, _| parse_krb5_principalname(a))(i)?;
+        let (i, caddr) = opt(complete(parse_ber_tagged_explicit_g(11, |a, _| {
+            parse_krb5_hostaddresses(a)
+        })))(i)?;
+        let caddr = caddr.unwrap_or_default();
+        let part = EncKdcRepPart {
+            key,
+            last_req,
+            nonce,
+            key_expiration,
+            flags,
+            authtime,
+            starttime,
+            endtime,
+            renew_till,
+            srealm,
+            sname,
+            caddr,
+        };
+        Ok((i, part))
+    })(i)
+}
+
+/// Parse the decrypted part of a Kerberos AS Reply
+///
+/// <pre>
+/// EncASRepPart    ::= [APPLICATION 25] EncKDCRepPart
+/// </pre>
+pub fn parse_enc_as_rep_part(i: &[u8]) -> IResult<&[u8], EncKdcRepPart, BerError> {
+    parse_ber_tagged_explicit_g(BerTag(25), |i, hdr| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_enc_kdc_rep_part)(i)
+    })(i)
+}
+
+/// Parse the decrypted part of a Kerberos TGS Reply
+///
+/// <pre>
+/// EncTGSRepPart   ::= [APPLICATION 26] EncKDCRepPart
+/// </pre>
+pub fn parse_enc_tgs_rep_part(i: &[u8]) -> IResult<&[u8], EncKdcRepPart, BerError> {
+    parse_ber_tagged_explicit_g(BerTag(26), |i, hdr| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_enc_kdc_rep_part)(i)
     })(i)
 }
 
 /// Parse a Kerberos Error
 ///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_ERROR` with `BerError::BerValueError`.
+///
 /// <pre>
 /// KRB-ERROR       ::= [APPLICATION 30] SEQUENCE {
 ///         pvno            [0] INTEGER (5),
@@ -437,13 +930,17 @@ pub fn parse_tgs_rep(i: &[u8]) -> IResult<&[u8], KdcRep, BerError> {
 /// </pre>
 pub fn parse_krb_error(i: &[u8]) -> IResult<&[u8], KrbError, BerError> {
     parse_ber_tagged_explicit_g(BerTag(30), |i, hdr| {
-        if !hdr.is_application() {
-            return Err(Err::Error(BerError::InvalidTag));
-        }
-        parse_ber_sequence_defined_g(|i, _| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_ber_sequence_defined_g(|i, _| {
             let (i, pvno) = parse_ber_tagged_explicit_g(0, |a, _| parse_der_u32(a))(i)?;
+            if pvno != 5 {
+                return Err(Err::Error(BerError::Custom(5)));
+            }
             let (i, msg_type) =
                 parse_ber_tagged_explicit_g(1, |a, _| map(parse_der_u32, MessageType)(a))(i)?;
+            if msg_type != MessageType::KRB_ERROR {
+                return Err(Err::Error(BerError::BerValueError));
+            }
             let (i, ctime) = opt(complete(parse_ber_tagged_explicit_g(2, |a, _| {
                 parse_kerberos_time(a)
             })))(i)?;
@@ -485,10 +982,57 @@ pub fn parse_krb_error(i: &[u8]) -> IResult<&[u8], KrbError, BerError> {
                 edata,
             };
             Ok((i, err))
-        })(i)
+        }))(i)
     })(i)
 }
 
+/// Parse a Windows KERB-ERROR-DATA structure, from the e-data field of a KRB-ERROR
+///
+/// <pre>
+/// KERB-ERROR-DATA ::= SEQUENCE {
+///         data-type       [1] INTEGER,
+///         data-value      [2] OCTET STRING OPTIONAL
+/// }
+/// </pre>
+pub fn parse_kerb_error_data_windows<'a>(
+    i: &'a [u8],
+) -> IResult<&'a [u8], KerbErrorData<'a>, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, data_type) = parse_ber_tagged_explicit_g(1, |a, _| parse_der_int32(a))(i)?;
+        let (i, data_value) = opt(complete(parse_ber_tagged_explicit_g(2, |a, _| {
+            map_res(parse_ber_octetstring, |o| o.as_slice())(a)
+        })))(i)?;
+        let data = KerbErrorData {
+            data_type,
+            data_value,
+        };
+        Ok((i, data))
+    })(i)
+}
+
+/// Parse a Windows KERB-EXT-ERROR structure, from the data value of a KERB-ERROR-DATA
+///
+/// This structure is not DER-encoded: it is made of three little-endian 32-bit integers.
+///
+/// <pre>
+/// KERB-EXT-ERROR  ::= {
+///         status          UINT32 -- NTSTATUS
+///         reserved        UINT32
+///         flags           UINT32
+/// }
+/// </pre>
+pub fn parse_kerb_ext_error(i: &[u8]) -> IResult<&[u8], KerbExtError, BerError> {
+    let (i, status) = le_u32(i)?;
+    let (i, reserved) = le_u32(i)?;
+    let (i, flags) = le_u32(i)?;
+    let err = KerbExtError {
+        status,
+        reserved,
+        flags,
+    };
+    Ok((i, err))
+}
+
 /// Parse Kerberos PA-Data
 ///
 /// <pre>
@@ -516,8 +1060,171 @@ fn parse_krb5_padata_sequence(i: &[u8]) -> IResult<&[u8], Vec<PAData>, BerError>
     parse_ber_sequence_of_v(parse_krb5_padata)(i)
 }
 
+/// Parse the value of a PA-PAC-REQUEST PA-Data
+///
+/// Returns the value of the `include-pac` field. Some clients do not use the DER encoding of
+/// `TRUE` (`0xff`), so any non-zero value is accepted as `true`.
+///
+/// <pre>
+/// KERB-PA-PAC-REQUEST ::= SEQUENCE {
+///         include-pac     [0] BOOLEAN
+/// }
+/// </pre>
+pub fn parse_pa_pac_request(i: &[u8]) -> IResult<&[u8], bool, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        parse_ber_tagged_explicit_g(0, |a, _| map_res(parse_ber_bool, |o| o.as_bool())(a))(i)
+    })(i)
+}
+
+/// Parse the value of a PA-PAC-OPTIONS PA-Data
+///
+/// Returns the `flags` field.
+///
+/// <pre>
+/// PA-PAC-OPTIONS ::= SEQUENCE {
+///         flags           [0] PAC-OPTIONS-FLAGS
+/// }
+///
+/// PAC-OPTIONS-FLAGS ::= KerberosFlags
+///         -- Claims (0)
+///         -- Branch Aware (1)
+///         -- Forward to Full DC (2)
+///         -- Resource-based Constrained Delegation (3)
+/// </pre>
+pub fn parse_pa_pac_options(i: &[u8]) -> IResult<&[u8], DerObject, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        parse_ber_tagged_explicit_g(0, |a, _| parse_kerberos_flags(a))(i)
+    })(i)
+}
+
+/// Parse the value of a PA-ENCRYPTED-CHALLENGE PA-Data (FAST, RFC6113)
+///
+/// <pre>
+/// PA-ENCRYPTED-CHALLENGE ::= EncryptedData -- Encrypted PA-ENC-TS-ENC
+/// </pre>
+#[inline]
+pub fn parse_pa_encrypted_challenge(i: &[u8]) -> IResult<&[u8], EncryptedData, BerError> {
+    parse_encrypted(i)
+}
+
+/// Parse the value of a PA-ENC-TIMESTAMP PA-Data
+///
+/// <pre>
+/// PA-ENC-TIMESTAMP        ::= EncryptedData -- PA-ENC-TS-ENC
+/// </pre>
+#[inline]
+pub fn parse_pa_enc_timestamp(i: &[u8]) -> IResult<&[u8], EncryptedData, BerError> {
+    parse_encrypted(i)
+}
+
+/// Parse the decrypted value of a PA-ENC-TIMESTAMP PA-Data
+///
+/// <pre>
+/// PA-ENC-TS-ENC           ::= SEQUENCE {
+///         patimestamp     [0] KerberosTime -- client's time --,
+///         pausec          [1] Microseconds OPTIONAL
+/// }
+/// </pre>
+pub fn parse_pa_enc_ts_enc(i: &[u8]) -> IResult<&[u8], PaEncTsEnc, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, patimestamp) = parse_ber_tagged_explicit_g(0, |a, _| parse_kerberos_time(a))(i)?;
+        let (i, pausec) = opt(complete(parse_ber_tagged_explicit_g(1, |a, _| {
+            parse_der_microseconds(a)
+        })))(i)?;
+        let ts = PaEncTsEnc {
+            patimestamp,
+            pausec,
+        };
+        Ok((i, ts))
+    })(i)
+}
+
+/// Parse a Kerberos ETYPE-INFO entry
+///
+/// <pre>
+/// ETYPE-INFO-ENTRY        ::= SEQUENCE {
+///         etype           [0] Int32,
+///         salt            [1] OCTET STRING OPTIONAL
+/// }
+/// </pre>
+pub fn parse_etype_info_entry<'a>(i: &'a [u8]) -> IResult<&'a [u8], ETypeInfoEntry<'a>, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, etype) =
+            parse_ber_tagged_explicit_g(0, |a, _| map(parse_der_int32, EncryptionType)(a))(i)?;
+        let (i, salt) = opt(complete(parse_ber_tagged_explicit_g(1, |a, _| {
+            map_res(parse_ber_octetstring, |o| o.as_slice())(a)
+        })))(i)?;
+        let entry = ETypeInfoEntry { etype, salt };
+        Ok((i, entry))
+    })(i)
+}
+
+/// Parse the value of a PA-ETYPE-INFO PA-Data
+///
+/// <pre>
+/// ETYPE-INFO              ::= SEQUENCE OF ETYPE-INFO-ENTRY
+/// </pre>
+pub fn parse_etype_info(i: &[u8]) -> IResult<&[u8], Vec<ETypeInfoEntry>, BerError> {
+    parse_ber_sequence_of_v(parse_etype_info_entry)(i)
+}
+
+/// Parse a Kerberos ETYPE-INFO2 entry
+///
+/// <pre>
+/// ETYPE-INFO2-ENTRY       ::= SEQUENCE {
+///         etype           [0] Int32,
+///         salt            [1] KerberosString OPTIONAL,
+///         s2kparams       [2] OCTET STRING OPTIONAL
+/// }
+/// </pre>
+///
+/// Like `parse_kerberos_string_lenient`, the salt is also accepted as a UTF8String, IA5String or
+/// BMPString. `salt_bytes` contains the content of the string, without decoding.
+pub fn parse_etype_info2_entry<'a>(
+    i: &'a [u8],
+) -> IResult<&'a [u8], ETypeInfo2Entry<'a>, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, etype) =
+            parse_ber_tagged_explicit_g(0, |a, _| map(parse_der_int32, EncryptionType)(a))(i)?;
+        let (i, salt_object) = opt(complete(parse_ber_tagged_explicit_g(1, |a, _| {
+            parse_kerberos_string_bytes(a)
+        })))(i)?;
+        let (i, s2kparams) = opt(complete(parse_ber_tagged_explicit_g(2, |a, _| {
+            map_res(parse_ber_octetstring, |o| o.as_slice())(a)
+        })))(i)?;
+        let salt = match salt_object {
+            Some((BerTag::BmpString, s)) => match decode_bmp_string(s) {
+                Some(s) => Some(s),
+                None => return Err(Err::Error(BerError::BerValueError)),
+            },
+            Some((_, s)) => Some(String::from_utf8_lossy(s).into_owned()),
+            None => None,
+        };
+        let salt_bytes = salt_object.map(|(_, s)| s);
+        let entry = ETypeInfo2Entry {
+            etype,
+            salt,
+            salt_bytes,
+            s2kparams,
+        };
+        Ok((i, entry))
+    })(i)
+}
+
+/// Parse the value of a PA-ETYPE-INFO2 PA-Data
+///
+/// <pre>
+/// ETYPE-INFO2             ::= SEQUENCE SIZE (1..MAX) OF ETYPE-INFO2-ENTRY
+/// </pre>
+pub fn parse_etype_info2(i: &[u8]) -> IResult<&[u8], Vec<ETypeInfo2Entry>, BerError> {
+    parse_ber_sequence_of_v(parse_etype_info2_entry)(i)
+}
+
 /// Parse a Kerberos AP Request
 ///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_AP_REQ` with `BerError::BerValueError`.
+///
 /// <pre>
 /// AP-REQ          ::= [APPLICATION 14] SEQUENCE {
 ///         pvno            [0] INTEGER (5),
@@ -532,15 +1239,19 @@ fn parse_krb5_padata_sequence(i: &[u8]) -> IResult<&[u8], Vec<PAData>, BerError>
 ///         -- use-session-key(1),
 ///         -- mutual-required(2)
 /// </pre>
-pub fn parse_ap_req<'a>(i: Vec<u8>) -> IResult<&'a [u8], Cow<'a, ApReq<'a>>, BerError> {
+pub fn parse_ap_req(i: &[u8]) -> IResult<&[u8], ApReq, BerError> {
     parse_ber_tagged_explicit_g(BerTag(14), |i, hdr| {
-        if !hdr.is_application() {
-            return Err(Err::Error(BerError::InvalidTag));
-        }
-        parse_ber_sequence_defined_g(|i, _| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_ber_sequence_defined_g(|i, _| {
             let (i, pvno) = parse_ber_tagged_explicit_g(0, |a, _| parse_der_u32(a))(i)?;
+            if pvno != 5 {
+                return Err(Err::Error(BerError::Custom(5)));
+            }
             let (i, msg_type) =
                 parse_ber_tagged_explicit_g(1, |a, _| map(parse_der_u32, MessageType)(a))(i)?;
+            if msg_type != MessageType::KRB_AP_REQ {
+                return Err(Err::Error(BerError::BerValueError));
+            }
             let (i, ap_options) =
                 parse_ber_tagged_explicit_g(2, |a, _| parse_kerberos_flags(a))(i)?;
             let (i, ticket) = parse_ber_tagged_explicit_g(3, |a, _| parse_krb5_ticket(a))(i)?;
@@ -552,13 +1263,74 @@ pub fn parse_ap_req<'a>(i: Vec<u8>) -> IResult<&'a [u8], Cow<'a, ApReq<'a>>, Ber
                 ticket,
                 authenticator: Cow::Owned(authenticator),
             };
-            Ok((i, Cow::Owned(req)))
-        })(i)
+            Ok((i, req))
+        }))(i)
+    })(i)
+}
+
+/// Parse a Kerberos Authenticator
+///
+/// An `authenticator-vno` other than 5 is rejected with `BerError::Custom(5)`.
+///
+/// <pre>
+/// Authenticator   ::= [APPLICATION 2] SEQUENCE  {
+///         authenticator-vno       [0] INTEGER (5),
+///         crealm                  [1] Realm,
+///         cname                   [2] PrincipalName,
+///         cksum                   [3] Checksum OPTIONAL,
+///         cusec                   [4] Microseconds,
+///         ctime                   [5] KerberosTime,
+///         subkey                  [6] EncryptionKey OPTIONAL,
+///         seq-number              [7] UInt32 OPTIONAL,
+///         authorization-data      [8] AuthorizationData OPTIONAL
+/// }
+/// </pre>
+pub fn parse_authenticator(i: &[u8]) -> IResult<&[u8], Authenticator, BerError> {
+    parse_ber_tagged_explicit_g(BerTag(2), |i, hdr| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_ber_sequence_defined_g(|i, _| {
+            let (i, authenticator_vno) =
+                parse_ber_tagged_explicit_g(0, |a, _| parse_der_u32(a))(i)?;
+            if authenticator_vno != 5 {
+                return Err(Err::Error(BerError::Custom(5)));
+            }
+            let (i, crealm) = parse_ber_tagged_explicit_g(1, |a, _| parse_krb5_realm(a))(i)?;
+            let (i, cname) = parse_ber_tagged_explicit_g(2, |a, _| parse_krb5_principalname(a))(i)?;
+            let (i, cksum) = opt(complete(parse_ber_tagged_explicit_g(3, |a, _| {
+                parse_checksum(a)
+            })))(i)?;
+            let (i, cusec) = parse_ber_tagged_explicit_g(4, |a, _| parse_der_microseconds(a))(i)?;
+            let (i, ctime) = parse_ber_tagged_explicit_g(5, |a, _| parse_kerberos_time(a))(i)?;
+            let (i, subkey) = opt(complete(parse_ber_tagged_explicit_g(6, |a, _| {
+                parse_encryption_key(a)
+            })))(i)?;
+            let (i, seq_number) = opt(complete(parse_ber_tagged_explicit_g(7, |a, _| {
+                parse_der_u32(a)
+            })))(i)?;
+            let (i, authorization_data) = opt(complete(parse_ber_tagged_explicit_g(8, |a, _| {
+                parse_authorization_data(a)
+            })))(i)?;
+            let auth = Authenticator {
+                authenticator_vno,
+                crealm,
+                cname,
+                cksum,
+                cusec,
+                ctime,
+                subkey,
+                seq_number,
+                authorization_data,
+            };
+            Ok((i, auth))
+        }))(i)
     })(i)
 }
 
 /// Parse a Kerberos AP Reply
 ///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_AP_REP` with `BerError::BerValueError`.
+///
 /// <pre>
 /// AP-REP          ::= [APPLICATION 15] SEQUENCE {
 ///         pvno            [0] INTEGER (5),
@@ -568,13 +1340,17 @@ pub fn parse_ap_req<'a>(i: Vec<u8>) -> IResult<&'a [u8], Cow<'a, ApReq<'a>>, Ber
 /// </pre>
 pub fn parse_ap_rep(i: &[u8]) -> IResult<&[u8], ApRep, BerError> {
     parse_ber_tagged_explicit_g(BerTag(15), |i, hdr| {
-        if !hdr.is_application() {
-            return Err(Err::Error(BerError::InvalidTag));
-        }
-        parse_ber_sequence_defined_g(|i, _| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_ber_sequence_defined_g(|i, _| {
             let (i, pvno) = parse_ber_tagged_explicit_g(0, |a, _| parse_der_u32(a))(i)?;
+            if pvno != 5 {
+                return Err(Err::Error(BerError::Custom(5)));
+            }
             let (i, msg_type) =
                 parse_ber_tagged_explicit_g(1, |a, _| map(parse_der_u32, MessageType)(a))(i)?;
+            if msg_type != MessageType::KRB_AP_REP {
+                return Err(Err::Error(BerError::BerValueError));
+            }
             let (i, enc_part) = parse_ber_tagged_explicit_g(2, |a, _| parse_encrypted(a))(i)?;
             let rep = ApRep {
                 pvno,
@@ -582,6 +1358,373 @@ pub fn parse_ap_rep(i: &[u8]) -> IResult<&[u8], ApRep, BerError> {
                 enc_part: Cow::Owned(enc_part),
             };
             Ok((i, rep))
+        }))(i)
+    })(i)
+}
+
+/// Parse the decrypted part of a Kerberos AP Reply
+///
+/// <pre>
+/// EncAPRepPart    ::= [APPLICATION 27] SEQUENCE {
+///         ctime           [0] KerberosTime,
+///         cusec           [1] Microseconds,
+///         subkey          [2] EncryptionKey OPTIONAL,
+///         seq-number      [3] UInt32 OPTIONAL
+/// }
+/// </pre>
+pub fn parse_enc_ap_rep_part(i: &[u8]) -> IResult<&[u8], EncApRepPart, BerError> {
+    parse_ber_tagged_explicit_g(BerTag(27), |i, hdr| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_ber_sequence_defined_g(|i, _| {
+            let (i, ctime) = parse_ber_tagged_explicit_g(0, |a, _| parse_kerberos_time(a))(i)?;
+            let (i, cusec) = parse_ber_tagged_explicit_g(1, |a, _| parse_der_microseconds(a))(i)?;
+            let (i, subkey) = opt(complete(parse_ber_tagged_explicit_g(2, |a, _| {
+                parse_encryption_key(a)
+            })))(i)?;
+            let (i, seq_number) = opt(complete(parse_ber_tagged_explicit_g(3, |a, _| {
+                parse_der_u32(a)
+            })))(i)?;
+            let part = EncApRepPart {
+                ctime,
+                cusec,
+                subkey,
+                seq_number,
+            };
+            Ok((i, part))
+        }))(i)
+    })(i)
+}
+
+/// Parse a Kerberos KRB-SAFE-BODY
+///
+/// <pre>
+/// KRB-SAFE-BODY   ::= SEQUENCE {
+///         user-data       [0] OCTET STRING,
+///         timestamp       [1] KerberosTime OPTIONAL,
+///         usec            [2] Microseconds OPTIONAL,
+///         seq-number      [3] UInt32 OPTIONAL,
+///         s-address       [4] HostAddress,
+///         r-address       [5] HostAddress OPTIONAL
+/// }
+/// </pre>
+pub fn parse_krb_safe_body<'a>(i: &'a [u8]) -> IResult<&'a [u8], KrbSafeBody<'a>, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, user_data) = parse_ber_tagged_explicit_g(0, |a, _| {
+            map_res(parse_ber_octetstring, |o| o.as_slice())(a)
+        })(i)?;
+        let (i, timestamp) = opt(complete(parse_ber_tagged_explicit_g(1, |a, _| {
+            parse_kerberos_time(a)
+        })))(i)?;
+        let (i, usec) = opt(complete(parse_ber_tagged_explicit_g(2, |a, _| {
+            parse_der_microseconds(a)
+        })))(i)?;
+        let (i, seq_number) = opt(complete(parse_ber_tagged_explicit_g(3, |a, _| {
+            parse_der_u32(a)
+        })))(i)?;
+        let (i, s_address) = parse_ber_tagged_explicit_g(4, |a, _| parse_krb5_hostaddress(a))(i)?;
+        let (i, r_address) = opt(complete(parse_ber_tagged_explicit_g(5, |a, _| {
+            parse_krb5_hostaddress(a)
+        })))(i)?;
+        let body = KrbSafeBody {
+            user_data,
+            timestamp,
+            usec,
+            seq_number,
+            s_address,
+            r_address,
+        };
+        Ok((i, body))
+    })(i)
+}
+
+/// Parse a Kerberos KRB-SAFE message
+///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_SAFE` with `BerError::BerValueError`.
+///
+/// <pre>
+/// KRB-SAFE        ::= [APPLICATION 20] SEQUENCE {
+///         pvno            [0] INTEGER (5),
+///         msg-type        [1] INTEGER (20),
+///         safe-body       [2] KRB-SAFE-BODY,
+///         cksum           [3] Checksum
+/// }
+/// </pre>
+pub fn parse_krb_safe(i: &[u8]) -> IResult<&[u8], KrbSafe, BerError> {
+    parse_ber_tagged_explicit_g(BerTag(20), |i, hdr| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_ber_sequence_defined_g(|i, _| {
+            let (i, pvno) = parse_ber_tagged_explicit_g(0, |a, _| parse_der_u32(a))(i)?;
+            if pvno != 5 {
+                return Err(Err::Error(BerError::Custom(5)));
+            }
+            let (i, msg_type) =
+                parse_ber_tagged_explicit_g(1, |a, _| map(parse_der_u32, MessageType)(a))(i)?;
+            if msg_type != MessageType::KRB_SAFE {
+                return Err(Err::Error(BerError::BerValueError));
+            }
+            let (i, safe_body) = parse_ber_tagged_explicit_g(2, |a, _| parse_krb_safe_body(a))(i)?;
+            let (i, cksum) = parse_ber_tagged_explicit_g(3, |a, _| parse_checksum(a))(i)?;
+            let safe = KrbSafe {
+                pvno,
+                msg_type,
+                safe_body,
+                cksum,
+            };
+            Ok((i, safe))
+        }))(i)
+    })(i)
+}
+
+/// Parse a Kerberos KRB-CRED message
+///
+/// A `pvno` other than 5 is rejected with `BerError::Custom(5)`, and a `msg-type` other than
+/// `KRB_CRED` with `BerError::BerValueError`.
+///
+/// <pre>
+/// KRB-CRED        ::= [APPLICATION 22] SEQUENCE {
+///         pvno            [0] INTEGER (5),
+///         msg-type        [1] INTEGER (22),
+///         tickets         [2] SEQUENCE OF Ticket,
+///         enc-part        [3] EncryptedData -- EncKrbCredPart
+/// }
+/// </pre>
+pub fn parse_krb_cred(i: &[u8]) -> IResult<&[u8], KrbCred, BerError> {
+    parse_ber_tagged_explicit_g(BerTag(22), |i, hdr| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_ber_sequence_defined_g(|i, _| {
+            let (i, pvno) = parse_ber_tagged_explicit_g(0, |a, _| parse_der_u32(a))(i)?;
+            if pvno != 5 {
+                return Err(Err::Error(BerError::Custom(5)));
+            }
+            let (i, msg_type) =
+                parse_ber_tagged_explicit_g(1, |a, _| map(parse_der_u32, MessageType)(a))(i)?;
+            if msg_type != MessageType::KRB_CRED {
+                return Err(Err::Error(BerError::BerValueError));
+            }
+            let (i, tickets) = parse_ber_tagged_explicit_g(2, |a, _| {
+                parse_ber_sequence_of_v(parse_krb5_ticket)(a)
+            })(i)?;
+            let (i, enc_part) = parse_ber_tagged_explicit_g(3, |a, _| parse_encrypted(a))(i)?;
+            let cred = KrbCred {
+                pvno,
+                msg_type,
+                tickets,
+                enc_part,
+            };
+            Ok((i, cred))
+        }))(i)
+    })(i)
+}
+
+/// Parse a Kerberos KrbCredInfo
+///
+/// <pre>
+/// KrbCredInfo     ::= SEQUENCE {
+///         key             [0] EncryptionKey,
+///         prealm          [1] Realm OPTIONAL,
+///         pname           [2] PrincipalName OPTIONAL,
+///         flags           [3] TicketFlags OPTIONAL,
+///         authtime        [4] KerberosTime OPTIONAL,
+///         starttime       [5] KerberosTime OPTIONAL,
+///         endtime         [6] KerberosTime OPTIONAL,
+///         renew-till      [7] KerberosTime OPTIONAL,
+///         srealm          [8] Realm OPTIONAL,
+///         sname           [9] PrincipalName OPTIONAL,
+///         caddr           [10] HostAddresses OPTIONAL
+/// }
+/// </pre>
+pub fn parse_krb_cred_info<'a>(i: &'a [u8]) -> IResult<&'a [u8], KrbCredInfo<'a>, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, key) = parse_ber_tagged_explicit_g(0, |a, _| parse_encryption_key(a))(i)?;
+        let (i, prealm) = opt(complete(parse_ber_tagged_explicit_g(1, |a, _| {
+            parse_krb5_realm(a)
+        })))(i)?;
+        let (i, pname) = opt(complete(parse_ber_tagged_explicit_g(2, |a, _| {
+            parse_krb5_principalname(a)
+        })))(i)?;
+        let (i, flags) = opt(complete(parse_ber_tagged_explicit_g(3, |a, _| {
+            parse_kerberos_flags(a)
+        })))(i)?;
+        let (i, authtime) = opt(complete(parse_ber_tagged_explicit_g(4, |a, _| {
+            parse_kerberos_time(a)
+        })))(i)?;
+        let (i, starttime) = opt(complete(parse_ber_tagged_explicit_g(5, |a, _| {
+            parse_kerberos_time(a)
+        })))(i)?;
+        let (i, endtime) = opt(complete(parse_ber_tagged_explicit_g(6, |a, _| {
+            parse_kerberos_time(a)
+        })))(i)?;
+        let (i, renew_till) = opt(complete(parse_ber_tagged_explicit_g(7, |a, _| {
+            parse_kerberos_time(a)
+        })))(i)?;
+        let (i, srealm) = opt(complete(parse_ber_tagged_explicit_g(8, |a, _| {
+            parse_krb5_realm(a)
+        })))(i)?;
+        let (i, sname) = opt(complete(parse_ber_tagged_explicit_g(9, |a, _| {
+            parse_krb5_principalname(a)
+        })))(i)?;
+        let (i, caddr) = opt(complete(parse_ber_tagged_explicit_g(10, |a, _| {
+            parse_krb5_hostaddresses(a)
+        })))(i)?;
+        let caddr = caddr.unwrap_or_default();
+        let info = KrbCredInfo {
+            key,
+            prealm,
+            pname,
+            flags,
+            authtime,
+            starttime,
+            endtime,
+            renew_till,
+            srealm,
+            sname,
+            caddr,
+        };
+        Ok((i, info))
+    })(i)
+}
+
+/// Parse the decrypted part of a Kerberos KRB-CRED message
+///
+/// <pre>
+/// EncKrbCredPart  ::= [APPLICATION 29] SEQUENCE {
+///         ticket-info     [0] SEQUENCE OF KrbCredInfo,
+///         nonce           [1] UInt32 OPTIONAL,
+///         timestamp       [2] KerberosTime OPTIONAL,
+///         usec            [3] Microseconds OPTIONAL,
+///         s-address       [4] HostAddress OPTIONAL,
+///         r-address       [5] HostAddress OPTIONAL
+/// }
+/// </pre>
+pub fn parse_enc_krb_cred_part(i: &[u8]) -> IResult<&[u8], EncKrbCredPart, BerError> {
+    parse_ber_tagged_explicit_g(BerTag(29), |i, hdr| {
+        check_application_header(&hdr)?;
+        all_consumed(parse_ber_sequence_defined_g(|i, _| {
+            let (i, ticket_info) = parse_ber_tagged_explicit_g(0, |a, _| {
+                parse_ber_sequence_of_v(parse_krb_cred_info)(a)
+            })(i)?;
+            let (i, nonce) = opt(complete(parse_ber_tagged_explicit_g(1, |a, _| {
+                parse_der_u32(a)
+            })))(i)?;
+            let (i, timestamp) = opt(complete(parse_ber_tagged_explicit_g(2, |a, _| {
+                parse_kerberos_time(a)
+            })))(i)?;
+            let (i, usec) = opt(complete(parse_ber_tagged_explicit_g(3, |a, _| {
+                parse_der_microseconds(a)
+            })))(i)?;
+            let (i, s_address) = opt(complete(parse_ber_tagged_explicit_g(4, |a, _| {
+                parse_krb5_hostaddress(a)
+            })))(i)?;
+            let (i, r_address) = opt(complete(parse_ber_tagged_explicit_g(5, |a, _| {
+                parse_krb5_hostaddress(a)
+            })))(i)?;
+            let part = EncKrbCredPart {
+                ticket_info,
+                nonce,
+                timestamp,
+                usec,
+                s_address,
+                r_address,
+            };
+            Ok((i, part))
+        }))(i)
+    })(i)
+}
+
+/// Read the message type of a Kerberos message, without parsing the message body
+///
+/// Only the application tag, the `pvno` and the `msg-type` fields are decoded. On success, the
+/// remaining input starts after the complete message.
+///
+/// The `msg-type` must be equal to the number of the application tag, or the message is rejected
+/// with `BerValueError`.
+pub fn peek_krb5_message_type(i: &[u8]) -> IResult<&[u8], MessageType, BerError> {
+    parse_ber_container(|i, hdr| {
+        check_application_header(&hdr)?;
+        // KDC-REQ starts with tag [1], not [0]
+        let app_tag = hdr.tag.0;
+        let msg_type_tag = match app_tag {
+            10 | 12 => 2,
+            _ => 1,
+        };
+        parse_ber_sequence_defined_g(move |i, _| {
+            let (i, _pvno) =
+                parse_ber_tagged_explicit_g(msg_type_tag - 1, |a, _| parse_der_u32(a))(i)?;
+            let (i, msg_type) = parse_ber_tagged_explicit_g(msg_type_tag, |a, _| {
+                map(parse_der_u32, MessageType)(a)
+            })(i)?;
+            if msg_type.0 != app_tag {
+                return Err(Err::Error(BerError::BerValueError));
+            }
+            Ok((i, msg_type))
         })(i)
     })(i)
 }
+
+// Parse an explicitly tagged object, without decoding its content
+fn skip_tagged<'a>(tag: u32) -> impl FnMut(&'a [u8]) -> IResult<&'a [u8], (), BerError> {
+    parse_ber_tagged_explicit_g(tag, |a, _| Ok((a, ())))
+}
+
+/// Test if the input is a KDC request (AS-REQ or TGS-REQ) for the `krbtgt` service
+///
+/// Only the fields preceding the server name are decoded, and nothing is allocated. This is
+/// meant to be used as a cheap filter before parsing the full message. Returns `false` if the
+/// input is not a KDC request, or has no server name.
+pub fn is_krbtgt_request(i: &[u8]) -> bool {
+    let res: IResult<&[u8], bool, BerError> = parse_ber_container(|i, hdr| {
+        check_application_header(&hdr)?;
+        if hdr.tag != BerTag(10) && hdr.tag != BerTag(12) {
+            return Err(Err::Error(BerError::InvalidTag));
+        }
+        parse_ber_sequence_defined_g(|i, _| {
+            let (i, _pvno) = skip_tagged(1)(i)?;
+            let (i, _msg_type) = skip_tagged(2)(i)?;
+            let (i, _padata) = opt(complete(skip_tagged(3)))(i)?;
+            parse_ber_tagged_explicit_g(4, |a, _| {
+                parse_ber_sequence_defined_g(|i, _| {
+                    let (i, _kdc_options) = skip_tagged(0)(i)?;
+                    let (i, _cname) = opt(complete(skip_tagged(1)))(i)?;
+                    let (i, _realm) = skip_tagged(2)(i)?;
+                    parse_ber_tagged_explicit_g(3, |a, _| parse_sname_is_krbtgt(a))(i)
+                })(a)
+            })(i)
+        })(i)
+    })(i);
+    res.map(|(_, b)| b).unwrap_or(false)
+}
+
+fn parse_sname_is_krbtgt(i: &[u8]) -> IResult<&[u8], bool, BerError> {
+    parse_ber_sequence_defined_g(|i, _| {
+        let (i, _name_type) = skip_tagged(0)(i)?;
+        parse_ber_tagged_explicit_g(1, |a, _| {
+            parse_ber_sequence_defined_g(|i, _| {
+                let (i, first) = parse_der(i)?;
+                Ok((i, kerberos_string_is_krbtgt(&first)))
+            })(a)
+        })(i)
+    })(i)
+}
+
+// Compare a KerberosString to `krbtgt` (ignoring case), accepting the same string types as
+// `parse_kerberos_string_lenient`, without allocating
+fn kerberos_string_is_krbtgt(obj: &DerObject) -> bool {
+    if obj.header.class != BerClass::Universal {
+        return false;
+    }
+    let s = match obj.as_slice() {
+        Ok(s) => s,
+        Err(_) => return false,
+    };
+    match obj.header.tag {
+        BerTag::GeneralString | BerTag::Utf8String | BerTag::Ia5String => is_krbtgt(s),
+        BerTag::BmpString => {
+            s.len() == 12
+                && s.chunks(2)
+                    .zip(b"krbtgt".iter())
+                    .all(|(c, b)| c[0] == 0 && c[1].eq_ignore_ascii_case(b))
+        }
+        _ => false,
+    }
+}
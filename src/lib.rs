@@ -31,15 +31,34 @@
 //! # }
 //! ```
 //!
+//! # Memory usage
+//!
+//! The parsers do not copy the input: ciphertexts, PA-Data values and other opaque fields
+//! are borrowed from the input buffer. Allocations are only made for `Vec` (SEQUENCE OF
+//! fields) and `String` (realms and names) values. Each element of a `Vec` and each
+//! character of a `String` is decoded from at least one byte of input, so the memory
+//! allocated for a message is proportional to its size. The only recursive parser is
+//! `parse_ad_if_relevant`, for nested AD-IF-RELEVANT authorization data: recursion is
+//! capped at `MAX_AD_IF_RELEVANT_DEPTH` levels, and deeper inputs fail with `BerMaxDepth`.
+//!
+//! To bound memory usage, limit the size of the input before calling the parsers.
+//!
 //! [RFC4120]: https://tools.ietf.org/html/rfc4120
 
 #![deny(/*missing_docs,*/unsafe_code,
         unstable_features,
         unused_import_braces, unused_qualifications)]
 
+pub mod key_usage;
 pub mod krb5;
 pub mod krb5_parser;
 
 mod krb5_constants;
+mod krb5_encoder;
 mod krb5_errors;
 pub use krb5_errors::*;
+
+// re-export the parsing crates used in the public API, so users do not have to
+// depend on the exact same versions
+pub use der_parser;
+pub use nom;
@@ -1,5 +1,7 @@
+use der_parser::error::BerError;
 use kerberos_parser::krb5::*;
 use kerberos_parser::krb5_parser::*;
+use nom::Err;
 
 #[test]
 fn test_parse_kerberos_string() {
@@ -54,6 +56,40 @@ fn test_parse_principalname2() {
     assert_eq!(res, Ok((empty, expected)));
 }
 
+#[test]
+fn test_parse_lenient_strings() {
+    let empty = &b""[..];
+    // UTF8String
+    let bytes = &[0x0c, 0x05, 0x4a, 0x6f, 0x6e, 0x65, 0x73];
+    let res = parse_krb5_realm(bytes);
+    assert_eq!(res, Ok((empty, Realm(String::from("Jones")))));
+    // IA5String
+    let bytes = &[0x16, 0x05, 0x4a, 0x6f, 0x6e, 0x65, 0x73];
+    let res = parse_krb5_realm(bytes);
+    assert_eq!(res, Ok((empty, Realm(String::from("Jones")))));
+    // BMPString
+    let bytes = &[0x1e, 0x06, 0x00, 0x4a, 0x00, 0xf6, 0x00, 0x65];
+    let res = parse_krb5_realm(bytes);
+    assert_eq!(res, Ok((empty, Realm(String::from("J\u{f6}e")))));
+    // BMPString with an odd length
+    let bytes = &[0x1e, 0x03, 0x00, 0x4a, 0x00];
+    assert!(parse_krb5_realm(bytes).is_err());
+    // OCTET STRING is not a string type
+    let bytes = &[0x04, 0x05, 0x4a, 0x6f, 0x6e, 0x65, 0x73];
+    assert!(parse_krb5_realm(bytes).is_err());
+    // principal name using UTF8String components
+    let bytes = &[
+        0x30, 0x11, 0xa0, 0x03, 0x02, 0x01, 0x01, 0xa1, 0x0a, 0x30, 0x08, 0x0c, 0x06, 0x4a, 0xc3,
+        0xb6, 0x6e, 0x65, 0x73,
+    ];
+    let expected = PrincipalName {
+        name_type: NameType::KRB_NT_PRINCIPAL,
+        name_string: vec![String::from("J\u{f6}nes")],
+    };
+    let res = parse_krb5_principalname(bytes);
+    assert_eq!(res, Ok((empty, expected)));
+}
+
 static KRB5_TICKET: &[u8] = include_bytes!("../assets/krb5-ticket.bin");
 #[test]
 fn test_parse_ticket() {
@@ -82,6 +118,45 @@ fn test_parse_ticket() {
     }
 }
 
+#[test]
+fn test_ticket_builder() {
+    // re-encoding a parsed ticket gives the original bytes
+    let (_, tkt) = parse_krb5_ticket(KRB5_TICKET).expect("parsing failed");
+    let der = TicketBuilder::new(
+        tkt.realm.clone(),
+        tkt.sname.clone(),
+        tkt.enc_part.clone().into_owned(),
+    )
+    .build();
+    assert_eq!(der, KRB5_TICKET);
+    // synthetic ticket
+    let enc_part = EncryptedData {
+        etype: EncryptionType::RC4_HMAC,
+        kvno: Some(0x8000_0000),
+        cipher: std::borrow::Cow::Borrowed(&[0xaa; 200][..]),
+    };
+    let sname = PrincipalName {
+        name_type: NameType::KRB_NT_SRV_INST,
+        name_string: vec![String::from("krbtgt"), String::from("EXAMPLE.COM")],
+    };
+    let builder = TicketBuilder::new(Realm(String::from("EXAMPLE.COM")), sname.clone(), enc_part);
+    let der = builder.build();
+    let (rem, tkt) = parse_krb5_ticket(&der).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(tkt.tkt_vno, 5);
+    assert_eq!(tkt.realm, Realm(String::from("EXAMPLE.COM")));
+    assert_eq!(tkt.sname, sname);
+    assert_eq!(tkt.enc_part.etype, EncryptionType::RC4_HMAC);
+    assert_eq!(tkt.enc_part.kvno, Some(0x8000_0000));
+    assert_eq!(tkt.enc_part.cipher_len(), 200);
+    // only version 5 is accepted by the parser
+    let der = builder.tkt_vno(4).build();
+    assert_eq!(
+        parse_krb5_ticket(&der),
+        Err(Err::Error(BerError::Custom(5)))
+    );
+}
+
 static AS_REQ: &[u8] = include_bytes!("../assets/as-req.bin");
 #[test]
 fn test_parse_as_req() {
@@ -114,6 +189,262 @@ fn test_parse_as_req() {
     }
 }
 
+#[test]
+fn test_parse_as_req_length_mismatch() {
+    // declare 2 more bytes in the application tag than used by the KDC-REQ
+    let mut bytes = AS_REQ.to_vec();
+    bytes[3] += 2;
+    bytes.extend_from_slice(&[0, 0]);
+    let res = parse_as_req(&bytes);
+    assert_eq!(res, Err(Err::Error(BerError::InvalidLength)));
+}
+
+#[test]
+fn test_parse_as_req_wrong_tag_encoding() {
+    // context-specific class instead of application, with the same tag number
+    let mut bytes = AS_REQ.to_vec();
+    bytes[0] = 0xaa;
+    let res = parse_as_req(&bytes);
+    assert_eq!(res, Err(Err::Error(BerError::InvalidTag)));
+    assert_eq!(
+        peek_krb5_message_type(&bytes),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+}
+
+#[test]
+fn test_parse_tgs_req_no_optional_fields() {
+    // TGS-REQ with no padata, and a body without any optional field
+    let bytes = &[
+        0x6c, 0x45, 0x30, 0x43, 0xa1, 0x03, 0x02, 0x01, 0x05, 0xa2, 0x03, 0x02, 0x01, 0x0c, 0xa4,
+        0x37, 0x30, 0x35, 0xa0, 0x07, 0x03, 0x05, 0x00, 0x40, 0x81, 0x00, 0x10, 0xa2, 0x08, 0x1b,
+        0x06, 0x44, 0x45, 0x4e, 0x59, 0x44, 0x43, 0xa5, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x33, 0x37,
+        0x30, 0x39, 0x31, 0x33, 0x30, 0x32, 0x34, 0x38, 0x30, 0x35, 0x5a, 0xa7, 0x06, 0x02, 0x04,
+        0x12, 0x34, 0x56, 0x78, 0xa8, 0x05, 0x30, 0x03, 0x02, 0x01, 0x17,
+    ];
+    let (rem, req) = parse_tgs_req(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(req.msg_type, MessageType::KRB_TGS_REQ);
+    assert!(req.padata.is_empty());
+    let body = &req.req_body;
+    assert_eq!(body.cname, None);
+    assert_eq!(body.realm, Realm(String::from("DENYDC")));
+    assert_eq!(body.sname, None);
+    assert_eq!(body.from, None);
+    assert_eq!(body.till, KerberosTime(&b"20370913024805Z"[..]));
+    assert_eq!(body.rtime, None);
+    assert_eq!(body.nonce, 0x1234_5678);
+    assert_eq!(req.nonce(), 0x1234_5678);
+    assert_eq!(body.etype, vec![EncryptionType::RC4_HMAC]);
+    assert!(body.addresses.is_empty());
+    assert_eq!(body.enc_authorization_data, None);
+    assert!(body.additional_tickets.is_empty());
+}
+
+#[test]
+fn test_parse_tgs_req_unknown_trailing_fields() {
+    // TGS-REQ with an unknown [5] field in KDC-REQ, and an unknown [12] field in KDC-REQ-BODY
+    let bytes = &[
+        0x6c, 0x4f, 0x30, 0x4d, 0xa1, 0x03, 0x02, 0x01, 0x05, 0xa2, 0x03, 0x02, 0x01, 0x0c, 0xa4,
+        0x3c, 0x30, 0x3a, 0xa0, 0x07, 0x03, 0x05, 0x00, 0x40, 0x81, 0x00, 0x10, 0xa2, 0x08, 0x1b,
+        0x06, 0x44, 0x45, 0x4e, 0x59, 0x44, 0x43, 0xa5, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x33, 0x37,
+        0x30, 0x39, 0x31, 0x33, 0x30, 0x32, 0x34, 0x38, 0x30, 0x35, 0x5a, 0xa7, 0x06, 0x02, 0x04,
+        0x12, 0x34, 0x56, 0x78, 0xa8, 0x05, 0x30, 0x03, 0x02, 0x01, 0x17, 0xac, 0x03, 0x02, 0x01,
+        0x01, 0xa5, 0x03, 0x04, 0x01, 0xff,
+    ];
+    let (rem, req) = parse_tgs_req(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(req.req_body.nonce, 0x1234_5678);
+    assert_eq!(req.req_body.etype, vec![EncryptionType::RC4_HMAC]);
+    assert!(req.req_body.additional_tickets.is_empty());
+}
+
+#[test]
+fn test_kdc_req_duplicate_padata() {
+    // AS-REQ with two PA-ENC-TIMESTAMP, around a PA-PAC-REQUEST
+    let bytes = &[
+        0x6a, 0x74, 0x30, 0x72, 0xa1, 0x03, 0x02, 0x01, 0x05, 0xa2, 0x03, 0x02, 0x01, 0x0a, 0xa3,
+        0x2d, 0x30, 0x2b, 0x30, 0x0a, 0xa1, 0x03, 0x02, 0x01, 0x02, 0xa2, 0x03, 0x04, 0x01, 0x01,
+        0x30, 0x11, 0xa1, 0x04, 0x02, 0x02, 0x00, 0x80, 0xa2, 0x09, 0x04, 0x07, 0x30, 0x05, 0xa0,
+        0x03, 0x01, 0x01, 0xff, 0x30, 0x0a, 0xa1, 0x03, 0x02, 0x01, 0x02, 0xa2, 0x03, 0x04, 0x01,
+        0x02, 0xa4, 0x37, 0x30, 0x35, 0xa0, 0x07, 0x03, 0x05, 0x00, 0x40, 0x81, 0x00, 0x10, 0xa2,
+        0x08, 0x1b, 0x06, 0x44, 0x45, 0x4e, 0x59, 0x44, 0x43, 0xa5, 0x11, 0x18, 0x0f, 0x32, 0x30,
+        0x33, 0x37, 0x30, 0x39, 0x31, 0x33, 0x30, 0x32, 0x34, 0x38, 0x30, 0x35, 0x5a, 0xa7, 0x06,
+        0x02, 0x04, 0x12, 0x34, 0x56, 0x78, 0xa8, 0x05, 0x30, 0x03, 0x02, 0x01, 0x17,
+    ];
+    let (rem, req) = parse_as_req(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    let types: Vec<_> = req.padata.iter().map(|p| p.padata_type).collect();
+    assert_eq!(
+        types,
+        vec![PAType::PA_ENC_TS, PAType::PA_PAC_REQUEST, PAType::PA_ENC_TS]
+    );
+    let values: Vec<_> = req
+        .padata_by_type(PAType::PA_ENC_TS)
+        .map(|p| p.padata_value)
+        .collect();
+    assert_eq!(values, vec![&[0x01][..], &[0x02][..]]);
+    assert_eq!(req.padata_by_type(PAType::PA_PAC_REQUEST).count(), 1);
+    assert_eq!(req.padata_by_type(PAType::PA_ETYPE_INFO2).count(), 0);
+}
+
+#[test]
+fn test_is_krbtgt_request() {
+    assert!(is_krbtgt_request(AS_REQ));
+    // change the server name to krbtgu/DENYDC
+    let mut bytes = AS_REQ.to_vec();
+    let idx = bytes
+        .windows(6)
+        .position(|w| w == b"krbtgt")
+        .expect("no krbtgt");
+    bytes[idx + 5] = b'u';
+    assert!(!is_krbtgt_request(&bytes));
+    // krbtgt component encoded as an UTF8String, as accepted by parse_as_req
+    let mut bytes = AS_REQ.to_vec();
+    assert_eq!(bytes[idx - 2], 0x1b);
+    bytes[idx - 2] = 0x0c;
+    let (_, req) = parse_as_req(&bytes).expect("parsing failed");
+    assert_eq!(req.target_spn(), Some("krbtgt/DENYDC".to_string()));
+    assert!(is_krbtgt_request(&bytes));
+    assert!(!is_krbtgt_request(AS_REP));
+    assert!(!is_krbtgt_request(&AS_REQ[..40]));
+}
+
+#[test]
+fn test_kdc_req_user_to_user() {
+    let (_, req) = parse_as_req(AS_REQ).expect("parsing failed");
+    assert!(!req.is_user_to_user());
+    // set the ENC-TKT-IN-SKEY option
+    let mut bytes = AS_REQ.to_vec();
+    bytes[129] |= 0x08;
+    let (_, mut req) = parse_as_req(&bytes).expect("parsing failed");
+    assert!(!req.is_user_to_user());
+    let (_, tkt) = parse_krb5_ticket(KRB5_TICKET).expect("parsing failed");
+    req.req_body.additional_tickets.push(tkt);
+    assert!(req.is_user_to_user());
+}
+
+#[test]
+fn test_kdc_req_pac_requested() {
+    let (_, mut req) = parse_as_req(AS_REQ).expect("parsing failed");
+    assert!(req.pac_requested());
+    let no_pac = &[0x30, 0x05, 0xa0, 0x03, 0x01, 0x01, 0x00];
+    assert_eq!(parse_pa_pac_request(no_pac), Ok((&b""[..], false)));
+    // BER encoding of TRUE
+    let ber_true = &[0x30, 0x05, 0xa0, 0x03, 0x01, 0x01, 0x01];
+    assert_eq!(parse_pa_pac_request(ber_true), Ok((&b""[..], true)));
+    req.padata = vec![PAData::new(PAType::PA_PAC_REQUEST, no_pac)];
+    assert!(!req.pac_requested());
+    req.padata.clear();
+    assert!(req.pac_requested());
+}
+
+#[test]
+fn test_kdc_req_pac_behavior() {
+    let (_, mut req) = parse_as_req(AS_REQ).expect("parsing failed");
+    let behavior = req.pac_behavior();
+    assert!(behavior.include_pac);
+    assert!(!behavior.claims);
+    assert!(!behavior.resource_based_constrained_delegation);
+    // PA-PAC-OPTIONS with Claims and Resource-based Constrained Delegation
+    let pac_options = &[
+        0x30, 0x09, 0xa0, 0x07, 0x03, 0x05, 0x00, 0x90, 0x00, 0x00, 0x00,
+    ];
+    let (_, flags) = parse_pa_pac_options(pac_options).expect("parsing failed");
+    assert_eq!(flags.as_slice(), Ok(&[0x90, 0x00, 0x00, 0x00][..]));
+    req.padata
+        .push(PAData::new(PAType::PA_PAC_OPTIONS, pac_options));
+    assert_eq!(
+        req.pac_behavior(),
+        PacBehavior {
+            include_pac: true,
+            claims: true,
+            branch_aware: false,
+            forward_to_full_dc: false,
+            resource_based_constrained_delegation: true,
+        }
+    );
+}
+
+#[test]
+fn test_parse_pa_encrypted_challenge() {
+    // same encoding as the PA-ENC-TIMESTAMP value of the AS-REQ
+    let res = parse_pa_encrypted_challenge(&AS_REQ[33..96]);
+    match res {
+        Ok((rem, enc)) => {
+            assert!(rem.is_empty());
+            assert_eq!(enc.etype, EncryptionType::RC4_HMAC);
+            assert_eq!(enc.kvno, None);
+            assert_eq!(enc.cipher.len(), 52);
+        }
+        _ => panic!("parsing failed"),
+    }
+    assert_eq!(
+        format!("{:?}", PAType::PA_ENCRYPTED_CHALLENGE),
+        "pa-encrypted-challenge"
+    );
+}
+
+#[test]
+fn test_encrypted_data_borrowed() {
+    let input = &AS_REQ[33..96];
+    let (_, enc) = parse_encrypted(input).expect("parsing failed");
+    assert_eq!(enc.cipher_len(), 52);
+    match enc.cipher {
+        std::borrow::Cow::Borrowed(c) => assert_eq!(c, &input[11..]),
+        _ => panic!("ciphertext was copied"),
+    }
+}
+
+#[test]
+fn test_parse_encrypted_trailing_data() {
+    // parse_encrypted does not require the input to end after the EncryptedData
+    let mut bytes = AS_REQ[33..96].to_vec();
+    bytes.extend_from_slice(&[0x05, 0x00]);
+    let (rem, enc) = parse_encrypted(&bytes).expect("parsing failed");
+    assert_eq!(rem, &[0x05, 0x00]);
+    assert_eq!(enc.cipher_len(), 52);
+}
+
+#[test]
+fn test_parse_encrypted_empty_cipher() {
+    let bytes = &[
+        0x30, 0x09, 0xa0, 0x03, 0x02, 0x01, 0x17, 0xa2, 0x02, 0x04, 0x00,
+    ];
+    let (rem, enc) = parse_encrypted(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(enc.etype, EncryptionType::RC4_HMAC);
+    assert_eq!(enc.kvno, None);
+    assert!(enc.cipher.is_empty());
+}
+
+#[test]
+fn test_kdc_req_cross_realm_referral() {
+    let (_, mut req) = parse_as_req(AS_REQ).expect("parsing failed");
+    assert_eq!(req.req_body.is_cross_realm_referral(), None);
+    req.req_body.sname = Some(PrincipalName {
+        name_type: NameType::KRB_NT_SRV_INST,
+        name_string: vec![String::from("krbtgt"), String::from("OTHER.COM")],
+    });
+    assert_eq!(
+        req.req_body.is_cross_realm_referral(),
+        Some(("DENYDC", "OTHER.COM"))
+    );
+    req.req_body.sname.as_mut().unwrap().name_string[0] = String::from("KRBTGT");
+    assert_eq!(
+        req.req_body.is_cross_realm_referral(),
+        Some(("DENYDC", "OTHER.COM"))
+    );
+}
+
+#[test]
+fn test_kdc_req_target_spn() {
+    let (_, mut req) = parse_as_req(AS_REQ).expect("parsing failed");
+    assert_eq!(req.target_spn(), Some(String::from("krbtgt/DENYDC")));
+    req.req_body.sname = None;
+    assert_eq!(req.target_spn(), None);
+}
+
 static AS_REP: &[u8] = include_bytes!("../assets/as-rep.bin");
 #[test]
 fn test_parse_as_rep() {
@@ -139,6 +470,75 @@ fn test_parse_as_rep() {
     }
 }
 
+#[test]
+fn test_default_salt() {
+    let (_, rep) = parse_as_rep(AS_REP).expect("parsing failed");
+    // same value as the PA-PW-SALT sent by the KDC
+    assert_eq!(default_salt(&rep.crealm, &rep.cname), "DENYDC.COMdes");
+    assert_eq!(rep.padata[0].padata_value, b"DENYDC.COMdes");
+    let cname = PrincipalName {
+        name_type: NameType::KRB_NT_SRV_HST,
+        name_string: vec![String::from("host"), String::from("www.example.com")],
+    };
+    assert_eq!(
+        default_salt(&Realm(String::from("EXAMPLE.COM")), &cname),
+        "EXAMPLE.COMhostwww.example.com"
+    );
+}
+
+#[test]
+fn test_kdc_rep_etype_info2() {
+    let (_, mut rep) = parse_as_rep(AS_REP).expect("parsing failed");
+    // this reply only has a PA-PW-SALT
+    assert_eq!(rep.padata.len(), 1);
+    assert_eq!(rep.padata[0].padata_type, PAType::PA_PW_SALT);
+    assert_eq!(rep.etype_info2(), None);
+    rep.padata
+        .push(PAData::new(PAType::PA_ETYPE_INFO2, ETYPE_INFO2));
+    let entries = rep.etype_info2().expect("no ETYPE-INFO2");
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].etype, EncryptionType::AES256_CTS_HMAC_SHA1_96);
+}
+
+#[test]
+fn test_kdc_rep_principals() {
+    let (_, mut rep) = parse_as_rep(AS_REP).expect("parsing failed");
+    assert_eq!(rep.client_principal(), "des@DENYDC.COM");
+    assert_eq!(
+        rep.service_principal(),
+        Some(String::from("krbtgt/DENYDC.COM@DENYDC.COM"))
+    );
+    rep.ticket.sname.name_string.clear();
+    assert_eq!(rep.service_principal(), None);
+}
+
+#[test]
+fn test_kdc_rep_realm_consistency() {
+    let (_, mut rep) = parse_as_rep(AS_REP).expect("parsing failed");
+    assert!(rep.realm_consistency());
+    rep.ticket.realm = Realm(String::from("OTHER.COM"));
+    assert!(!rep.realm_consistency());
+    rep.msg_type = MessageType::KRB_TGS_REP;
+    assert!(rep.realm_consistency());
+}
+
+#[test]
+fn test_negotiated_downgrade() {
+    let (_, mut req) = parse_as_req(AS_REQ).expect("parsing failed");
+    let (_, rep) = parse_as_rep(AS_REP).expect("parsing failed");
+    assert_eq!(rep.ticket.enc_part.etype, EncryptionType::RC4_HMAC);
+    req.req_body.etype = vec![EncryptionType::RC4_HMAC, EncryptionType::DES_CBC_MD5];
+    assert_eq!(negotiated_downgrade(&req, &rep), None);
+    req.req_body.etype = vec![
+        EncryptionType::AES256_CTS_HMAC_SHA1_96,
+        EncryptionType::RC4_HMAC,
+    ];
+    assert_eq!(
+        negotiated_downgrade(&req, &rep),
+        Some((EncryptionType::RC4_HMAC, &req.req_body.etype[..]))
+    );
+}
+
 static AP_REQ: &[u8] = include_bytes!("../assets/ap-req.bin");
 #[test]
 fn test_parse_ap_req() {
@@ -164,6 +564,34 @@ fn test_parse_ap_req() {
     }
 }
 
+#[test]
+fn test_parse_ap_req_invalid_header() {
+    let (rem, req) = parse_ap_req(AP_REQ).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(req.authenticator.etype, EncryptionType::DES_CBC_MD5);
+    // the pvno value is at offset 12, the msg-type value at offset 17
+    let mut bytes = AP_REQ.to_vec();
+    bytes[12] = 4;
+    assert_eq!(parse_ap_req(&bytes), Err(Err::Error(BerError::Custom(5))));
+    let mut bytes = AP_REQ.to_vec();
+    bytes[17] = 15;
+    assert_eq!(
+        parse_ap_req(&bytes),
+        Err(Err::Error(BerError::BerValueError))
+    );
+}
+
+#[test]
+fn test_ap_req_expects_reply() {
+    let (_, req) = parse_ap_req(AP_REQ).expect("parsing failed");
+    assert!(!req.expects_reply());
+    // set the MUTUAL-REQUIRED bit
+    let mut bytes = AP_REQ.to_vec();
+    bytes[23] = 0x20;
+    let (_, req) = parse_ap_req(&bytes).expect("parsing failed");
+    assert!(req.expects_reply());
+}
+
 static KRB_ERROR: &[u8] = include_bytes!("../assets/krb-error.bin");
 #[test]
 fn test_parse_krb_error() {
@@ -190,6 +618,173 @@ fn test_parse_krb_error() {
     }
 }
 
+#[test]
+fn test_parse_encryption_key() {
+    let bytes = &[
+        0x30, 0x29, 0xa0, 0x03, 0x02, 0x01, 0x12, 0xa1, 0x22, 0x04, 0x20, 0x10, 0x11, 0x12, 0x13,
+        0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, 0x20, 0x21, 0x22,
+        0x23, 0x24, 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2b, 0x2c, 0x2d, 0x2e, 0x2f,
+    ];
+    let empty = &b""[..];
+    let expected = EncryptionKey {
+        keytype: EncryptionType::AES256_CTS_HMAC_SHA1_96,
+        keyvalue: &bytes[11..],
+    };
+
+    let res = parse_encryption_key(bytes);
+    assert_eq!(res, Ok((empty, expected)));
+}
+
+static AP_REP: &[u8] = &[
+    0x6f, 0x29, 0x30, 0x27, 0xa0, 0x03, 0x02, 0x01, 0x05, 0xa1, 0x03, 0x02, 0x01, 0x0f, 0xa2, 0x1b,
+    0x30, 0x19, 0xa0, 0x03, 0x02, 0x01, 0x17, 0xa2, 0x12, 0x04, 0x10, 0x00, 0x01, 0x02, 0x03, 0x04,
+    0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+];
+#[test]
+fn test_parse_ap_rep() {
+    let (rem, rep) = parse_ap_rep(AP_REP).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(rep.pvno, 5);
+    assert_eq!(rep.msg_type, MessageType::KRB_AP_REP);
+    assert_eq!(rep.enc_part.etype, EncryptionType::RC4_HMAC);
+    assert_eq!(rep.enc_part.kvno, None);
+    assert_eq!(rep.enc_part.cipher, &AP_REP[27..]);
+    // invalid pvno
+    let mut bytes = AP_REP.to_vec();
+    bytes[8] = 4;
+    assert_eq!(parse_ap_rep(&bytes), Err(Err::Error(BerError::Custom(5))));
+    // invalid msg-type
+    let mut bytes = AP_REP.to_vec();
+    bytes[13] = 14;
+    assert_eq!(
+        parse_ap_rep(&bytes),
+        Err(Err::Error(BerError::BerValueError))
+    );
+}
+
+static ENC_AP_REP_PART: &[u8] = &[
+    0x7b, 0x41, 0x30, 0x3f, 0xa0, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36,
+    0x30, 0x39, 0x34, 0x30, 0x32, 0x39, 0x5a, 0xa1, 0x05, 0x02, 0x03, 0x01, 0xe2, 0x40, 0xa2, 0x1b,
+    0x30, 0x19, 0xa0, 0x03, 0x02, 0x01, 0x17, 0xa1, 0x12, 0x04, 0x10, 0x00, 0x01, 0x02, 0x03, 0x04,
+    0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0xa3, 0x06, 0x02, 0x04, 0x12,
+    0x34, 0x56, 0x78,
+];
+#[test]
+fn test_parse_enc_ap_rep_part() {
+    let res = parse_enc_ap_rep_part(ENC_AP_REP_PART);
+    match res {
+        Ok((rem, part)) => {
+            assert!(rem.is_empty());
+            assert_eq!(part.ctime, KerberosTime(b"20050816094029Z"));
+            assert_eq!(part.cusec, 123_456);
+            assert_eq!(
+                part.subkey,
+                Some(EncryptionKey {
+                    keytype: EncryptionType::RC4_HMAC,
+                    keyvalue: &ENC_AP_REP_PART[43..59],
+                })
+            );
+            assert_eq!(part.seq_number, Some(0x1234_5678));
+        }
+        _ => panic!("parsing failed"),
+    }
+}
+
+#[test]
+fn test_kerberos_time_ord() {
+    let (_, req) = parse_as_req(AS_REQ).expect("parsing failed");
+    let (_, err) = parse_krb_error(KRB_ERROR).expect("parsing failed");
+    assert_eq!(req.req_body.till, KerberosTime(&b"20370913024805Z"[..]));
+    assert_eq!(err.stime, KerberosTime(b"20050816094029Z"));
+    assert!(err.stime < req.req_body.till);
+}
+
+#[test]
+fn test_parse_kerberos_time() {
+    let bytes = b"\x18\x0f20050816094029Z";
+    let (rem, t) = parse_kerberos_time(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(t, KerberosTime(&b"20050816094029Z"[..]));
+    // fractional seconds are not allowed
+    let bytes = b"\x18\x1120050816094029.5Z";
+    assert!(parse_kerberos_time(bytes).is_err());
+}
+
+static KRB_ERROR_EMPTY_ETEXT: &[u8] = &[
+    0x7e, 0x54, 0x30, 0x52, 0xa0, 0x03, 0x02, 0x01, 0x05, 0xa1, 0x03, 0x02, 0x01, 0x1e, 0xa4, 0x11,
+    0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36, 0x30, 0x39, 0x34, 0x30, 0x32, 0x39,
+    0x5a, 0xa5, 0x03, 0x02, 0x01, 0x00, 0xa6, 0x03, 0x02, 0x01, 0x3c, 0xa9, 0x08, 0x1b, 0x06, 0x44,
+    0x45, 0x4e, 0x59, 0x44, 0x43, 0xaa, 0x1b, 0x30, 0x19, 0xa0, 0x03, 0x02, 0x01, 0x02, 0xa1, 0x12,
+    0x30, 0x10, 0x1b, 0x06, 0x6b, 0x72, 0x62, 0x74, 0x67, 0x74, 0x1b, 0x06, 0x44, 0x45, 0x4e, 0x59,
+    0x44, 0x43, 0xab, 0x02, 0x1b, 0x00,
+];
+#[test]
+fn test_parse_krb_error_empty_etext() {
+    let (rem, err) = parse_krb_error(KRB_ERROR_EMPTY_ETEXT).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(err.error_code, ErrorCode::KRB_ERR_GENERIC);
+    assert_eq!(err.etext, Some(String::new()));
+    // absent e-text
+    let (_, err) = parse_krb_error(KRB_ERROR).expect("parsing failed");
+    assert_eq!(err.etext, None);
+}
+
+#[test]
+fn test_padata_to_der() {
+    let empty = &b""[..];
+    // PA-PAC-REQUEST entry from the AS-REQ
+    let pa = PAData::new(PAType::PA_PAC_REQUEST, &AS_REQ[108..115]);
+    let der = pa.to_der();
+    assert_eq!(&der[..], &AS_REQ[96..115]);
+    assert_eq!(parse_krb5_padata(&der), Ok((empty, pa)));
+    // negative type, long value
+    let value = vec![0x5a; 200];
+    let pa = PAData::new(PAType(-129), &value);
+    let der = pa.to_der();
+    assert_eq!(parse_krb5_padata(&der), Ok((empty, pa)));
+}
+
+static ETYPE_INFO2: &[u8] = &[
+    0x30, 0x38, 0x30, 0x21, 0xa0, 0x03, 0x02, 0x01, 0x12, 0xa1, 0x12, 0x1b, 0x10, 0x45, 0x58, 0x41,
+    0x4d, 0x50, 0x4c, 0x45, 0x2e, 0x43, 0x4f, 0x4d, 0x6a, 0x6f, 0x73, 0xc3, 0xa9, 0xa2, 0x06, 0x04,
+    0x04, 0x00, 0x00, 0x10, 0x00, 0x30, 0x05, 0xa0, 0x03, 0x02, 0x01, 0x17, 0x30, 0x0c, 0xa0, 0x03,
+    0x02, 0x01, 0x11, 0xa1, 0x05, 0x1b, 0x03, 0xe9, 0x74, 0xe9,
+];
+#[test]
+fn test_parse_etype_info2() {
+    let res = parse_etype_info2(ETYPE_INFO2);
+    match res {
+        Ok((rem, entries)) => {
+            assert!(rem.is_empty());
+            assert_eq!(entries.len(), 3);
+            assert_eq!(entries[0].etype, EncryptionType::AES256_CTS_HMAC_SHA1_96);
+            assert_eq!(entries[0].salt, Some(String::from("EXAMPLE.COMjos\u{e9}")));
+            assert_eq!(entries[0].salt_bytes, Some(&b"EXAMPLE.COMjos\xc3\xa9"[..]));
+            assert_eq!(entries[0].s2kparams, Some(&[0x00, 0x00, 0x10, 0x00][..]));
+            assert_eq!(entries[1].etype, EncryptionType::RC4_HMAC);
+            assert_eq!(entries[1].salt, None);
+            assert_eq!(entries[1].salt_bytes, None);
+            // salt is not valid UTF-8: bytes are kept as-is
+            assert_eq!(entries[2].salt_bytes, Some(&[0xe9, 0x74, 0xe9][..]));
+            assert_eq!(entries[2].salt, Some(String::from("\u{fffd}t\u{fffd}")));
+        }
+        _ => panic!("parsing failed"),
+    }
+    // salt encoded as UTF8String
+    let mut bytes = ETYPE_INFO2.to_vec();
+    bytes[11] = 0x0c;
+    let (_, entries) = parse_etype_info2(&bytes).expect("parsing failed");
+    assert_eq!(entries[0].salt, Some(String::from("EXAMPLE.COMjos\u{e9}")));
+    assert_eq!(entries[0].salt_bytes, Some(&b"EXAMPLE.COMjos\xc3\xa9"[..]));
+    // salt encoded as BMPString
+    let bytes = [
+        0x30, 0x0d, 0xa0, 0x03, 0x02, 0x01, 0x12, 0xa1, 0x06, 0x1e, 0x04, 0x00, 0x41, 0x00, 0x42,
+    ];
+    let (_, entry) = parse_etype_info2_entry(&bytes).expect("parsing failed");
+    assert_eq!(entry.salt, Some(String::from("AB")));
+    assert_eq!(entry.salt_bytes, Some(&bytes[11..]));
+}
+
 #[test]
 fn test_parse_int32() {
     let empty = &b""[..];
@@ -218,6 +813,107 @@ fn test_parse_int32() {
     );
 }
 
+#[test]
+fn test_peek_message_type() {
+    let empty = &b""[..];
+    assert_eq!(
+        peek_krb5_message_type(AS_REQ),
+        Ok((empty, MessageType::KRB_AS_REQ))
+    );
+    assert_eq!(
+        peek_krb5_message_type(AS_REP),
+        Ok((empty, MessageType::KRB_AS_REP))
+    );
+    assert_eq!(
+        peek_krb5_message_type(AP_REQ),
+        Ok((empty, MessageType::KRB_AP_REQ))
+    );
+    assert_eq!(
+        peek_krb5_message_type(KRB_ERROR),
+        Ok((empty, MessageType::KRB_ERROR))
+    );
+}
+
+#[test]
+fn test_peek_message_type_mismatch() {
+    // [APPLICATION 10] (AS-REQ) with msg-type 13 (TGS-REP)
+    let mut bytes = AS_REQ.to_vec();
+    assert_eq!(bytes[17], 10);
+    bytes[17] = 13;
+    assert_eq!(
+        peek_krb5_message_type(&bytes),
+        Err(Err::Error(BerError::BerValueError))
+    );
+}
+
+#[test]
+fn test_kdc_req_body_preferred_etype() {
+    let mut body = KdcReqBody::default();
+    assert_eq!(body.weakest_preferred_etype(), None);
+    assert!(!body.prefers_rc4_first());
+    body.etype = vec![
+        EncryptionType::AES256_CTS_HMAC_SHA1_96,
+        EncryptionType::RC4_HMAC,
+    ];
+    assert_eq!(
+        body.weakest_preferred_etype(),
+        Some(EncryptionType::AES256_CTS_HMAC_SHA1_96)
+    );
+    assert!(!body.prefers_rc4_first());
+    body.etype.reverse();
+    assert_eq!(
+        body.weakest_preferred_etype(),
+        Some(EncryptionType::RC4_HMAC)
+    );
+    assert!(body.prefers_rc4_first());
+}
+
+#[test]
+fn test_kdc_req_body_default() {
+    let body = KdcReqBody {
+        realm: Realm(String::from("DENYDC")),
+        nonce: 42,
+        ..Default::default()
+    };
+    assert_eq!(body.realm, Realm(String::from("DENYDC")));
+    assert_eq!(body.cname, None);
+    assert!(body.etype.is_empty());
+    assert_eq!(PrincipalName::default().name_type, NameType::KRB_NT_UNKNOWN);
+}
+
+#[test]
+fn test_error_code_vendor() {
+    let empty = &b""[..];
+    // Windows-specific error code, outside of the RFC4120 range
+    let (rem, code) = parse_der_int32(&[0x02, 0x01, 0x6e]).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(ErrorCode(code), ErrorCode(110));
+    assert_eq!(format!("{:?}", ErrorCode(code)), "ErrorCode(110)");
+    // full i32 range
+    assert_eq!(
+        parse_der_int32(&[0x02, 0x04, 0x7f, 0xff, 0xff, 0xff]),
+        Ok((empty, i32::MAX))
+    );
+    assert_eq!(
+        parse_der_int32(&[0x02, 0x04, 0x80, 0x00, 0x00, 0x00]),
+        Ok((empty, i32::MIN))
+    );
+    assert_eq!(
+        format!("{:?}", ErrorCode::KDC_ERR_ETYPE_NOSUPP),
+        "KDC_ERR_ETYPE_NOSUPP"
+    );
+}
+
+#[test]
+fn test_message_type_direction() {
+    assert!(MessageType::KRB_AS_REQ.is_request());
+    assert!(!MessageType::KRB_AS_REQ.is_response());
+    assert!(MessageType::KRB_ERROR.is_response());
+    assert!(!MessageType::KRB_ERROR.is_request());
+    assert!(!MessageType::KRB_SAFE.is_request());
+    assert!(!MessageType::KRB_SAFE.is_response());
+}
+
 #[test]
 fn test_principalname_display() {
     let pn = PrincipalName {
@@ -226,3 +922,864 @@ fn test_principalname_display() {
     };
     assert_eq!("krb5/DOMAIN.COM", format!("{}", pn));
 }
+
+#[cfg(feature = "time")]
+static KRB_ERROR_SKEW: &[u8] = &[
+    0x7e, 0x63, 0x30, 0x61, 0xa0, 0x03, 0x02, 0x01, 0x05, 0xa1, 0x03, 0x02, 0x01, 0x1e, 0xa2, 0x11,
+    0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36, 0x30, 0x39, 0x34, 0x30, 0x30, 0x30,
+    0x5a, 0xa4, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36, 0x30, 0x39, 0x34,
+    0x30, 0x32, 0x39, 0x5a, 0xa5, 0x03, 0x02, 0x01, 0x00, 0xa6, 0x03, 0x02, 0x01, 0x25, 0xa9, 0x08,
+    0x1b, 0x06, 0x44, 0x45, 0x4e, 0x59, 0x44, 0x43, 0xaa, 0x1b, 0x30, 0x19, 0xa0, 0x03, 0x02, 0x01,
+    0x02, 0xa1, 0x12, 0x30, 0x10, 0x1b, 0x06, 0x6b, 0x72, 0x62, 0x74, 0x67, 0x74, 0x1b, 0x06, 0x44,
+    0x45, 0x4e, 0x59, 0x44, 0x43,
+];
+
+#[cfg(feature = "time")]
+#[test]
+fn test_krb_error_clock_skew() {
+    let (rem, err) = parse_krb_error(KRB_ERROR_SKEW).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(err.error_code, ErrorCode::KRB_AP_ERR_SKEW);
+    assert_eq!(err.clock_skew(), Some(chrono::Duration::seconds(29)));
+    // no ctime in this one
+    let (_, err) = parse_krb_error(KRB_ERROR).expect("parsing failed");
+    assert_eq!(err.clock_skew(), None);
+}
+
+#[test]
+fn test_principalname_service_class() {
+    let pn = PrincipalName {
+        name_type: NameType::KRB_NT_SRV_INST,
+        name_string: vec!["MSSQLSvc".to_string(), "db.domain.com:1433".to_string()],
+    };
+    assert_eq!(pn.service_class(), Some("MSSQLSvc"));
+    let pn = PrincipalName {
+        name_type: NameType::KRB_NT_PRINCIPAL,
+        name_string: vec!["user".to_string()],
+    };
+    assert_eq!(pn.service_class(), None);
+}
+
+#[test]
+fn test_krb_error_as_result() {
+    let (_, mut err) = parse_krb_error(KRB_ERROR).expect("parsing failed");
+    assert_eq!(
+        err.as_result(),
+        Err((ErrorCode::KDC_ERR_ETYPE_NOSUPP, None))
+    );
+    err.etext = Some(String::from("unsupported etype"));
+    assert_eq!(
+        err.as_result(),
+        Err((ErrorCode::KDC_ERR_ETYPE_NOSUPP, Some("unsupported etype")))
+    );
+    err.error_code = ErrorCode::KDC_ERR_NONE;
+    assert_eq!(err.as_result(), Ok(()));
+}
+
+#[test]
+fn test_parse_kerb_error_data_windows() {
+    let bytes = &[
+        0x30, 0x15, 0xa1, 0x03, 0x02, 0x01, 0x03, 0xa2, 0x0e, 0x04, 0x0c, 0x34, 0x02, 0x00, 0xc0,
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+    ];
+    let (rem, data) = parse_kerb_error_data_windows(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(data.data_type, KerbErrorData::KERB_ERR_TYPE_EXTENDED);
+    let ext = data.extended_error().expect("no extended error");
+    assert_eq!(ext.status, 0xc000_0234);
+    assert_eq!(ext.reserved, 0);
+    assert_eq!(ext.flags, 1);
+    // KRB_ERROR e-data is a METHOD-DATA, not a KERB-ERROR-DATA
+    let (_, err) = parse_krb_error(KRB_ERROR).expect("parsing failed");
+    assert_eq!(err.kerb_error_data(), None);
+}
+
+#[test]
+fn test_account_state_hint() {
+    assert_eq!(
+        ErrorCode::KDC_ERR_CLIENT_REVOKED.account_state_hint(),
+        Some(AccountState::Revoked)
+    );
+    assert_eq!(
+        ErrorCode::KDC_ERR_KEY_EXPIRED.account_state_hint(),
+        Some(AccountState::PasswordExpired)
+    );
+    assert_eq!(ErrorCode::KDC_ERR_PREAUTH_FAILED.account_state_hint(), None);
+    let (_, mut err) = parse_krb_error(KRB_ERROR).expect("parsing failed");
+    assert_eq!(err.account_state_hint(), None);
+    err.error_code = ErrorCode::KDC_ERR_CLIENT_REVOKED;
+    assert_eq!(err.account_state_hint(), Some(AccountState::Revoked));
+    // KERB-ERROR-DATA with a STATUS_ACCOUNT_LOCKED_OUT extended error
+    let edata = &[
+        0x04, 0x17, 0x30, 0x15, 0xa1, 0x03, 0x02, 0x01, 0x03, 0xa2, 0x0e, 0x04, 0x0c, 0x34, 0x02,
+        0x00, 0xc0, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+    ];
+    let (_, edata) = der_parser::der::parse_der(edata).expect("parsing failed");
+    err.edata = Some(edata);
+    assert_eq!(err.account_state_hint(), Some(AccountState::Locked));
+    // STATUS_ACCOUNT_EXPIRED
+    let edata = &[
+        0x04, 0x17, 0x30, 0x15, 0xa1, 0x03, 0x02, 0x01, 0x03, 0xa2, 0x0e, 0x04, 0x0c, 0x93, 0x01,
+        0x00, 0xc0, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+    ];
+    let (_, edata) = der_parser::der::parse_der(edata).expect("parsing failed");
+    err.edata = Some(edata);
+    assert_eq!(err.account_state_hint(), Some(AccountState::Expired));
+    let ext = KerbExtError {
+        status: KerbExtError::STATUS_ACCOUNT_EXPIRED,
+        reserved: 0,
+        flags: 1,
+    };
+    assert_eq!(ext.account_state_hint(), Some(AccountState::Expired));
+}
+
+#[test]
+fn test_encryption_type_names() {
+    assert_eq!(
+        EncryptionType::from_name("aes256-cts-hmac-sha1-96"),
+        Some(EncryptionType::AES256_CTS_HMAC_SHA1_96)
+    );
+    assert_eq!(
+        EncryptionType::from_name("RC4-HMAC"),
+        Some(EncryptionType::RC4_HMAC)
+    );
+    assert_eq!(EncryptionType::from_name("unknown"), None);
+    assert_eq!(EncryptionType(1000).name(), None);
+    for etype in EncryptionType::all_known() {
+        let name = etype.name().expect("missing name");
+        assert_eq!(EncryptionType::from_name(name), Some(*etype));
+        assert_eq!(format!("{:?}", etype), name);
+    }
+}
+
+#[test]
+fn test_parse_etype_info() {
+    // PA-ETYPE-INFO value, from the METHOD-DATA in the e-data of KRB_ERROR
+    let (rem, entries) = parse_etype_info(&KRB_ERROR[103..153]).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].etype, EncryptionType::DES_CBC_MD5);
+    assert_eq!(entries[0].salt, Some(&b"DENYDC.COMdes"[..]));
+    assert_eq!(entries[1].etype, EncryptionType::DES_CBC_CRC);
+    assert_eq!(entries[1].salt, Some(&b"DENYDC.COMdes"[..]));
+    // entry without salt
+    let bytes = &[0x30, 0x07, 0x30, 0x05, 0xa0, 0x03, 0x02, 0x01, 0x17];
+    let (_, entries) = parse_etype_info(bytes).expect("parsing failed");
+    assert_eq!(
+        entries,
+        vec![ETypeInfoEntry {
+            etype: EncryptionType::RC4_HMAC,
+            salt: None
+        }]
+    );
+}
+
+#[test]
+fn test_key_usage_for() {
+    use kerberos_parser::key_usage::{self, EncPartContext};
+    assert_eq!(
+        EncryptedData::key_usage_for(EncPartContext::AsRepEncPart),
+        key_usage::AS_REP_ENC_PART
+    );
+    assert_eq!(EncryptedData::key_usage_for(EncPartContext::Ticket), 2);
+    assert_eq!(
+        EncryptedData::key_usage_for(EncPartContext::TgsRepEncPartSubkey),
+        9
+    );
+    assert_eq!(
+        EncryptedData::key_usage_for(EncPartContext::ApReqAuthenticator),
+        11
+    );
+}
+
+#[test]
+fn test_principalname_host_xhst() {
+    let mut name = PrincipalName {
+        name_type: NameType::KRB_NT_SRV_XHST,
+        name_string: vec![
+            String::from("host"),
+            String::from("www"),
+            String::from("example"),
+            String::from("com"),
+        ],
+    };
+    assert_eq!(name.host_xhst(), Some(String::from("www.example.com")));
+    name.name_type = NameType::KRB_NT_SRV_HST;
+    assert_eq!(name.host_xhst(), None);
+    name.name_type = NameType::KRB_NT_SRV_XHST;
+    name.name_string.truncate(1);
+    assert_eq!(name.host_xhst(), None);
+}
+
+static AUTHENTICATOR: &[u8] = &[
+    0x62, 0x81, 0x9f, 0x30, 0x81, 0x9c, 0xa0, 0x03, 0x02, 0x01, 0x05, 0xa1, 0x0c, 0x1b, 0x0a, 0x44,
+    0x45, 0x4e, 0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d, 0xa2, 0x10, 0x30, 0x0e, 0xa0, 0x03, 0x02,
+    0x01, 0x01, 0xa1, 0x07, 0x30, 0x05, 0x1b, 0x03, 0x64, 0x65, 0x73, 0xa3, 0x25, 0x30, 0x23, 0xa0,
+    0x05, 0x02, 0x03, 0x00, 0x80, 0x03, 0xa1, 0x1a, 0x04, 0x18, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05,
+    0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15,
+    0x16, 0x17, 0xa4, 0x05, 0x02, 0x03, 0x01, 0xe2, 0x40, 0xa5, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30,
+    0x35, 0x30, 0x38, 0x31, 0x36, 0x30, 0x39, 0x34, 0x30, 0x32, 0x39, 0x5a, 0xa6, 0x1b, 0x30, 0x19,
+    0xa0, 0x03, 0x02, 0x01, 0x17, 0xa1, 0x12, 0x04, 0x10, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+    0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0xa7, 0x06, 0x02, 0x04, 0x12, 0x34, 0x56,
+    0x78, 0xa8, 0x0f, 0x30, 0x0d, 0x30, 0x0b, 0xa0, 0x03, 0x02, 0x01, 0x01, 0xa1, 0x04, 0x04, 0x02,
+    0x30, 0x00,
+];
+#[test]
+fn test_parse_authenticator() {
+    let (rem, auth) = parse_authenticator(AUTHENTICATOR).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(auth.authenticator_vno, 5);
+    assert_eq!(auth.crealm, Realm(String::from("DENYDC.COM")));
+    assert_eq!(
+        auth.cname,
+        PrincipalName {
+            name_type: NameType::KRB_NT_PRINCIPAL,
+            name_string: vec![String::from("des")],
+        }
+    );
+    assert_eq!(
+        auth.cksum,
+        Some(Checksum {
+            cksumtype: ChecksumType::GSSAPI,
+            checksum: &AUTHENTICATOR[58..82],
+        })
+    );
+    assert_eq!(auth.cusec, 123_456);
+    assert_eq!(auth.ctime, KerberosTime(&b"20050816094029Z"[..]));
+    assert_eq!(
+        auth.subkey,
+        Some(EncryptionKey {
+            keytype: EncryptionType::RC4_HMAC,
+            keyvalue: &AUTHENTICATOR[121..137],
+        })
+    );
+    assert_eq!(auth.seq_number, Some(0x1234_5678));
+    assert_eq!(
+        auth.authorization_data,
+        Some(vec![AuthorizationDataElement {
+            ad_type: AdType::AD_IF_RELEVANT,
+            ad_data: &AUTHENTICATOR[160..162],
+        }])
+    );
+    // only the mandatory fields
+    let bytes = &[
+        0x62, 0x3f, 0x30, 0x3d, 0xa0, 0x03, 0x02, 0x01, 0x05, 0xa1, 0x0c, 0x1b, 0x0a, 0x44, 0x45,
+        0x4e, 0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d, 0xa2, 0x10, 0x30, 0x0e, 0xa0, 0x03, 0x02,
+        0x01, 0x01, 0xa1, 0x07, 0x30, 0x05, 0x1b, 0x03, 0x64, 0x65, 0x73, 0xa4, 0x03, 0x02, 0x01,
+        0x00, 0xa5, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36, 0x30, 0x39,
+        0x34, 0x30, 0x32, 0x39, 0x5a,
+    ];
+    let (rem, auth) = parse_authenticator(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(auth.cksum, None);
+    assert_eq!(auth.cusec, 0);
+    assert_eq!(auth.subkey, None);
+    assert_eq!(auth.seq_number, None);
+    assert_eq!(auth.authorization_data, None);
+}
+
+static ENC_TICKET_PART: &[u8] = &[
+    0x63, 0x81, 0xd6, 0x30, 0x81, 0xd3, 0xa0, 0x07, 0x03, 0x05, 0x00, 0x40, 0xe1, 0x00, 0x00, 0xa1,
+    0x2b, 0x30, 0x29, 0xa0, 0x03, 0x02, 0x01, 0x12, 0xa1, 0x22, 0x04, 0x20, 0x00, 0x01, 0x02, 0x03,
+    0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13,
+    0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, 0xa2, 0x0c, 0x1b, 0x0a,
+    0x44, 0x45, 0x4e, 0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d, 0xa3, 0x10, 0x30, 0x0e, 0xa0, 0x03,
+    0x02, 0x01, 0x01, 0xa1, 0x07, 0x30, 0x05, 0x1b, 0x03, 0x64, 0x65, 0x73, 0xa4, 0x0b, 0x30, 0x09,
+    0xa0, 0x03, 0x02, 0x01, 0x01, 0xa1, 0x02, 0x04, 0x00, 0xa5, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30,
+    0x35, 0x30, 0x38, 0x31, 0x36, 0x30, 0x39, 0x34, 0x30, 0x32, 0x39, 0x5a, 0xa6, 0x11, 0x18, 0x0f,
+    0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36, 0x30, 0x39, 0x34, 0x30, 0x32, 0x39, 0x5a, 0xa7,
+    0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36, 0x31, 0x39, 0x34, 0x30, 0x32,
+    0x39, 0x5a, 0xa8, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x32, 0x33, 0x30, 0x39,
+    0x34, 0x30, 0x32, 0x39, 0x5a, 0xa9, 0x11, 0x30, 0x0f, 0x30, 0x0d, 0xa0, 0x03, 0x02, 0x01, 0x02,
+    0xa1, 0x06, 0x04, 0x04, 0xc0, 0xa8, 0x00, 0x01, 0xaa, 0x0f, 0x30, 0x0d, 0x30, 0x0b, 0xa0, 0x03,
+    0x02, 0x01, 0x01, 0xa1, 0x04, 0x04, 0x02, 0x30, 0x00,
+];
+#[test]
+fn test_parse_enc_ticket_part() {
+    let (rem, part) = parse_enc_ticket_part(ENC_TICKET_PART).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(part.flags.as_slice(), Ok(&[0x40, 0xe1, 0x00, 0x00][..]));
+    assert_eq!(part.key.keytype, EncryptionType::AES256_CTS_HMAC_SHA1_96);
+    assert_eq!(part.key.keyvalue, &ENC_TICKET_PART[28..60]);
+    assert_eq!(part.crealm, Realm(String::from("DENYDC.COM")));
+    assert_eq!(part.cname.to_string(), "des");
+    assert_eq!(
+        part.transited,
+        TransitedEncoding {
+            tr_type: TransitedType::DOMAIN_X500_COMPRESS,
+            contents: b"",
+        }
+    );
+    assert_eq!(part.authtime, KerberosTime(&b"20050816094029Z"[..]));
+    assert_eq!(part.starttime, Some(part.authtime));
+    assert_eq!(part.endtime, KerberosTime(&b"20050816194029Z"[..]));
+    assert_eq!(part.renew_till, Some(KerberosTime(&b"20050823094029Z"[..])));
+    assert_eq!(part.caddr.len(), 1);
+    assert_eq!(part.caddr[0].addr_type, AddressType::IPV4);
+    assert_eq!(part.caddr[0].address, &[192, 168, 0, 1]);
+    let ad = part.authorization_data.expect("no authorization data");
+    assert_eq!(ad.len(), 1);
+    assert_eq!(ad[0].ad_type, AdType::AD_IF_RELEVANT);
+    assert_eq!(ad[0].ad_data, &ENC_TICKET_PART[215..217]);
+}
+
+static ENC_AS_REP_PART: &[u8] = &[
+    0x79, 0x81, 0xc7, 0x30, 0x81, 0xc4, 0xa0, 0x2b, 0x30, 0x29, 0xa0, 0x03, 0x02, 0x01, 0x12, 0xa1,
+    0x22, 0x04, 0x20, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+    0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c,
+    0x1d, 0x1e, 0x1f, 0xa1, 0x1c, 0x30, 0x1a, 0x30, 0x18, 0xa0, 0x03, 0x02, 0x01, 0x00, 0xa1, 0x11,
+    0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36, 0x30, 0x39, 0x34, 0x30, 0x32, 0x39,
+    0x5a, 0xa2, 0x06, 0x02, 0x04, 0x12, 0x34, 0x56, 0x78, 0xa4, 0x07, 0x03, 0x05, 0x00, 0x40, 0xe0,
+    0x00, 0x00, 0xa5, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36, 0x30, 0x39,
+    0x34, 0x30, 0x32, 0x39, 0x5a, 0xa7, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31,
+    0x36, 0x31, 0x39, 0x34, 0x30, 0x32, 0x39, 0x5a, 0xa8, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35,
+    0x30, 0x38, 0x32, 0x33, 0x30, 0x39, 0x34, 0x30, 0x32, 0x39, 0x5a, 0xa9, 0x0c, 0x1b, 0x0a, 0x44,
+    0x45, 0x4e, 0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d, 0xaa, 0x1f, 0x30, 0x1d, 0xa0, 0x03, 0x02,
+    0x01, 0x02, 0xa1, 0x16, 0x30, 0x14, 0x1b, 0x06, 0x6b, 0x72, 0x62, 0x74, 0x67, 0x74, 0x1b, 0x0a,
+    0x44, 0x45, 0x4e, 0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d,
+];
+#[test]
+fn test_parse_enc_as_rep_part() {
+    let (rem, part) = parse_enc_as_rep_part(ENC_AS_REP_PART).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(part.key.keytype, EncryptionType::AES256_CTS_HMAC_SHA1_96);
+    assert_eq!(part.key.keyvalue, &ENC_AS_REP_PART[19..51]);
+    assert_eq!(
+        part.last_req,
+        vec![LastReqEntry {
+            lr_type: LastReqType::NONE,
+            lr_value: KerberosTime(&b"20050816094029Z"[..]),
+        }]
+    );
+    assert_eq!(part.nonce, 0x1234_5678);
+    assert_eq!(part.key_expiration, None);
+    assert_eq!(part.flags.as_slice(), Ok(&[0x40, 0xe0, 0x00, 0x00][..]));
+    assert_eq!(part.authtime, KerberosTime(&b"20050816094029Z"[..]));
+    assert_eq!(part.starttime, None);
+    assert_eq!(part.endtime, KerberosTime(&b"20050816194029Z"[..]));
+    assert_eq!(part.renew_till, Some(KerberosTime(&b"20050823094029Z"[..])));
+    assert_eq!(part.srealm, Realm(String::from("DENYDC.COM")));
+    assert_eq!(part.sname.to_string(), "krbtgt/DENYDC.COM");
+    assert!(part.caddr.is_empty());
+    // the same content, with the EncTGSRepPart tag
+    let mut bytes = ENC_AS_REP_PART.to_vec();
+    assert!(parse_enc_tgs_rep_part(&bytes).is_err());
+    bytes[0] = 0x7a;
+    let (_, tgs_part) = parse_enc_tgs_rep_part(&bytes).expect("parsing failed");
+    assert_eq!(tgs_part.nonce, part.nonce);
+    assert_eq!(tgs_part.sname, part.sname);
+}
+
+#[test]
+fn test_kdc_rep_is_referral() {
+    let (_, rep) = parse_as_rep(AS_REP).expect("parsing failed");
+    assert_eq!(rep.crealm, Realm(String::from("DENYDC.COM")));
+    let (_, mut part) = parse_enc_as_rep_part(ENC_AS_REP_PART).expect("parsing failed");
+    // krbtgt/DENYDC.COM@DENYDC.COM
+    assert_eq!(rep.is_referral(&part), None);
+    // cross-realm TGT krbtgt/OTHER.COM@DENYDC.COM
+    part.sname.name_string[1] = String::from("OTHER.COM");
+    assert_eq!(rep.is_referral(&part), Some(("DENYDC.COM", "OTHER.COM")));
+    part.sname.name_string[0] = String::from("KrbTgt");
+    assert_eq!(rep.is_referral(&part), Some(("DENYDC.COM", "OTHER.COM")));
+    // service ticket
+    part.sname.name_string[0] = String::from("host");
+    assert_eq!(rep.is_referral(&part), None);
+}
+
+static KRB_SAFE: &[u8] = &[
+    0x74, 0x62, 0x30, 0x60, 0xa0, 0x03, 0x02, 0x01, 0x05, 0xa1, 0x03, 0x02, 0x01, 0x14, 0xa2, 0x3b,
+    0x30, 0x39, 0xa0, 0x07, 0x04, 0x05, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0xa1, 0x11, 0x18, 0x0f, 0x32,
+    0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36, 0x30, 0x39, 0x34, 0x30, 0x32, 0x39, 0x5a, 0xa2, 0x05,
+    0x02, 0x03, 0x01, 0xe2, 0x40, 0xa3, 0x03, 0x02, 0x01, 0x2a, 0xa4, 0x0f, 0x30, 0x0d, 0xa0, 0x03,
+    0x02, 0x01, 0x02, 0xa1, 0x06, 0x04, 0x04, 0xc0, 0xa8, 0x00, 0x01, 0xa3, 0x17, 0x30, 0x15, 0xa0,
+    0x03, 0x02, 0x01, 0x10, 0xa1, 0x0e, 0x04, 0x0c, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+    0x08, 0x09, 0x0a, 0x0b,
+];
+#[test]
+fn test_parse_krb_safe() {
+    let (rem, safe) = parse_krb_safe(KRB_SAFE).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(safe.pvno, 5);
+    assert_eq!(safe.msg_type, MessageType::KRB_SAFE);
+    let body = &safe.safe_body;
+    assert_eq!(body.user_data, b"hello");
+    assert_eq!(body.timestamp, Some(KerberosTime(&b"20050816094029Z"[..])));
+    assert_eq!(body.usec, Some(123_456));
+    assert_eq!(body.seq_number, Some(42));
+    assert_eq!(body.s_address.addr_type, AddressType::IPV4);
+    assert_eq!(body.s_address.address, &KRB_SAFE[71..75]);
+    assert_eq!(body.r_address, None);
+    assert_eq!(safe.cksum.cksumtype, ChecksumType::HMAC_SHA1_96_AES256);
+    assert_eq!(safe.cksum.checksum, &KRB_SAFE[88..100]);
+    // wrong msg-type
+    let mut bytes = KRB_SAFE.to_vec();
+    bytes[13] = 21;
+    let res = parse_krb_safe(&bytes);
+    assert_eq!(res, Err(Err::Error(BerError::BerValueError)));
+}
+
+// KRB-CRED with an unencrypted (etype 0) EncKrbCredPart, as sent in some GSS-API delegation tokens
+static KRB_CRED: &[u8] = &[
+    0x76, 0x82, 0x01, 0x29, 0x30, 0x82, 0x01, 0x25, 0xa0, 0x03, 0x02, 0x01, 0x05, 0xa1, 0x03, 0x02,
+    0x01, 0x16, 0xa2, 0x5c, 0x30, 0x5a, 0x61, 0x58, 0x30, 0x56, 0xa0, 0x03, 0x02, 0x01, 0x05, 0xa1,
+    0x0c, 0x1b, 0x0a, 0x44, 0x45, 0x4e, 0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d, 0xa2, 0x1f, 0x30,
+    0x1d, 0xa0, 0x03, 0x02, 0x01, 0x02, 0xa1, 0x16, 0x30, 0x14, 0x1b, 0x06, 0x6b, 0x72, 0x62, 0x74,
+    0x67, 0x74, 0x1b, 0x0a, 0x44, 0x45, 0x4e, 0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d, 0xa3, 0x20,
+    0x30, 0x1e, 0xa0, 0x03, 0x02, 0x01, 0x12, 0xa1, 0x03, 0x02, 0x01, 0x02, 0xa2, 0x12, 0x04, 0x10,
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+    0xa3, 0x81, 0xba, 0x30, 0x81, 0xb7, 0xa0, 0x03, 0x02, 0x01, 0x00, 0xa2, 0x81, 0xaf, 0x04, 0x81,
+    0xac, 0x7d, 0x81, 0xa9, 0x30, 0x81, 0xa6, 0xa0, 0x81, 0x9e, 0x30, 0x81, 0x9b, 0x30, 0x81, 0x98,
+    0xa0, 0x2b, 0x30, 0x29, 0xa0, 0x03, 0x02, 0x01, 0x12, 0xa1, 0x22, 0x04, 0x20, 0x00, 0x01, 0x02,
+    0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12,
+    0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, 0xa1, 0x0c, 0x1b,
+    0x0a, 0x44, 0x45, 0x4e, 0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d, 0xa2, 0x10, 0x30, 0x0e, 0xa0,
+    0x03, 0x02, 0x01, 0x01, 0xa1, 0x07, 0x30, 0x05, 0x1b, 0x03, 0x64, 0x65, 0x73, 0xa3, 0x07, 0x03,
+    0x05, 0x00, 0x60, 0xa1, 0x00, 0x00, 0xa6, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38,
+    0x31, 0x36, 0x31, 0x39, 0x34, 0x30, 0x32, 0x39, 0x5a, 0xa8, 0x0c, 0x1b, 0x0a, 0x44, 0x45, 0x4e,
+    0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d, 0xa9, 0x1f, 0x30, 0x1d, 0xa0, 0x03, 0x02, 0x01, 0x02,
+    0xa1, 0x16, 0x30, 0x14, 0x1b, 0x06, 0x6b, 0x72, 0x62, 0x74, 0x67, 0x74, 0x1b, 0x0a, 0x44, 0x45,
+    0x4e, 0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d, 0xa1, 0x03, 0x02, 0x01, 0x07,
+];
+#[test]
+fn test_parse_krb_cred() {
+    let (rem, cred) = parse_krb_cred(KRB_CRED).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(cred.pvno, 5);
+    assert_eq!(cred.msg_type, MessageType::KRB_CRED);
+    assert_eq!(cred.tickets.len(), 1);
+    assert_eq!(cred.tickets[0].realm, Realm(String::from("DENYDC.COM")));
+    assert_eq!(cred.tickets[0].sname.to_string(), "krbtgt/DENYDC.COM");
+    assert_eq!(cred.enc_part.etype, EncryptionType(0));
+    assert_eq!(cred.enc_part.cipher.as_ref(), &KRB_CRED[129..]);
+    let (rem, part) = parse_enc_krb_cred_part(&cred.enc_part.cipher).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(part.nonce, Some(7));
+    assert_eq!(part.timestamp, None);
+    assert_eq!(part.s_address, None);
+    assert_eq!(part.ticket_info.len(), 1);
+    let info = &part.ticket_info[0];
+    assert_eq!(info.key.keytype, EncryptionType::AES256_CTS_HMAC_SHA1_96);
+    assert_eq!(info.key.keyvalue, &KRB_CRED[157..189]);
+    assert_eq!(info.prealm, Some(Realm(String::from("DENYDC.COM"))));
+    assert_eq!(
+        info.pname.as_ref().map(|p| p.to_string()),
+        Some("des".to_string())
+    );
+    assert!(info.flags.is_some());
+    assert_eq!(info.authtime, None);
+    assert_eq!(info.endtime, Some(KerberosTime(&b"20050816194029Z"[..])));
+    assert_eq!(info.sname, Some(cred.tickets[0].sname.clone()));
+    assert!(info.caddr.is_empty());
+}
+
+#[test]
+fn test_krb_cred_ticket_summaries() {
+    let (_, cred) = parse_krb_cred(KRB_CRED).expect("parsing failed");
+    assert_eq!(
+        cred.ticket_summaries(),
+        vec![(
+            PrincipalName {
+                name_type: NameType::KRB_NT_SRV_INST,
+                name_string: vec!["krbtgt".to_string(), "DENYDC.COM".to_string()],
+            },
+            EncryptionType::AES256_CTS_HMAC_SHA1_96
+        )]
+    );
+}
+
+#[test]
+fn test_parse_authorization_data() {
+    let bytes = &[
+        0x30, 0x1b, 0x30, 0x0b, 0xa0, 0x03, 0x02, 0x01, 0x01, 0xa1, 0x04, 0x04, 0x02, 0x30, 0x00,
+        0x30, 0x0c, 0xa0, 0x04, 0x02, 0x02, 0x00, 0x80, 0xa1, 0x04, 0x04, 0x02, 0x01, 0x02,
+    ];
+    let (rem, ad) = parse_authorization_data(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(ad.len(), 2);
+    assert_eq!(ad[0].ad_type, AdType::AD_IF_RELEVANT);
+    assert_eq!(ad[0].ad_data, &[0x30, 0x00]);
+    assert_eq!(ad[1].ad_type, AdType::AD_WIN2K_PAC);
+    assert_eq!(ad[1].ad_data, &[0x01, 0x02]);
+    assert_eq!(format!("{:?}", ad[1].ad_type), "AD-WIN2K-PAC");
+    // empty AuthorizationData
+    let (_, ad) = parse_authorization_data(&[0x30, 0x00]).expect("parsing failed");
+    assert!(ad.is_empty());
+}
+
+#[test]
+fn test_parse_ad_if_relevant() {
+    // AD-IF-RELEVANT wrapping an AD-WIN2K-PAC element
+    let bytes = &[
+        0x30, 0x1b, 0x30, 0x19, 0xa0, 0x03, 0x02, 0x01, 0x01, 0xa1, 0x12, 0x04, 0x10, 0x30, 0x0e,
+        0x30, 0x0c, 0xa0, 0x04, 0x02, 0x02, 0x00, 0x80, 0xa1, 0x04, 0x04, 0x02, 0x01, 0x02,
+    ];
+    let (_, ad) = parse_authorization_data(bytes).expect("parsing failed");
+    assert_eq!(ad.len(), 1);
+    let inner = ad[0].as_if_relevant().expect("not AD-IF-RELEVANT");
+    assert_eq!(inner.len(), 1);
+    assert_eq!(inner[0].ad_type, AdType::AD_WIN2K_PAC);
+    assert_eq!(inner[0].ad_data, &[0x01, 0x02]);
+    assert_eq!(inner[0].as_if_relevant(), None);
+}
+
+#[test]
+fn test_parse_ad_if_relevant_depth() {
+    // AuthorizationData with `levels` nested AD-IF-RELEVANT elements, around an empty one
+    fn nested(levels: usize) -> Vec<u8> {
+        let mut ad = vec![0x30, 0x00];
+        for _ in 0..levels {
+            let n = ad.len() as u8;
+            let mut v = vec![0x30, n + 11, 0x30, n + 9, 0xa0, 0x03, 0x02, 0x01, 0x01];
+            v.extend_from_slice(&[0xa1, n + 2, 0x04, n]);
+            v.extend_from_slice(&ad);
+            ad = v;
+        }
+        ad
+    }
+    let bytes = nested(MAX_AD_IF_RELEVANT_DEPTH - 1);
+    let (rem, ad) = parse_ad_if_relevant(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert!(ad[0].as_if_relevant().is_some());
+    let bytes = nested(MAX_AD_IF_RELEVANT_DEPTH);
+    let res = parse_ad_if_relevant(&bytes);
+    assert_eq!(res, Err(Err::Error(BerError::BerMaxDepth)));
+    // one level less below the outer element
+    let (_, ad) = parse_authorization_data(&bytes).expect("parsing failed");
+    assert!(ad[0].as_if_relevant().is_some());
+}
+
+#[test]
+fn test_parse_ad_kdc_issued() {
+    // AD-KDCIssued wrapping a KERB-AUTH-DATA-TOKEN-RESTRICTIONS element
+    let bytes = &[
+        0x30, 0x46, 0x30, 0x44, 0xa0, 0x03, 0x02, 0x01, 0x04, 0xa1, 0x3d, 0x04, 0x3b, 0x30, 0x39,
+        0xa0, 0x17, 0x30, 0x15, 0xa0, 0x03, 0x02, 0x01, 0x10, 0xa1, 0x0e, 0x04, 0x0c, 0x00, 0x01,
+        0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0xa1, 0x0c, 0x1b, 0x0a, 0x44,
+        0x45, 0x4e, 0x59, 0x44, 0x43, 0x2e, 0x43, 0x4f, 0x4d, 0xa3, 0x10, 0x30, 0x0e, 0x30, 0x0c,
+        0xa0, 0x04, 0x02, 0x02, 0x00, 0x8d, 0xa1, 0x04, 0x04, 0x02, 0x30, 0x00,
+    ];
+    let (_, ad) = parse_authorization_data(bytes).expect("parsing failed");
+    assert_eq!(ad[0].ad_type, AdType::AD_KDC_ISSUED);
+    assert_eq!(ad[0].as_if_relevant(), None);
+    let kdc_issued = ad[0].as_kdc_issued().expect("not AD-KDCIssued");
+    assert_eq!(
+        kdc_issued.ad_checksum,
+        Checksum {
+            cksumtype: ChecksumType::HMAC_SHA1_96_AES256,
+            checksum: &bytes[28..40],
+        }
+    );
+    assert_eq!(kdc_issued.i_realm, Some(Realm(String::from("DENYDC.COM"))));
+    assert_eq!(kdc_issued.i_sname, None);
+    assert_eq!(kdc_issued.elements.len(), 1);
+    assert_eq!(
+        kdc_issued.elements[0].ad_type,
+        AdType::KERB_AUTH_DATA_TOKEN_RESTRICTIONS
+    );
+    // missing elements
+    let mut content = vec![0x30, 0x27];
+    content.extend_from_slice(&bytes[15..54]);
+    let res = parse_ad_kdc_issued(&content);
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_parse_ad_and_or() {
+    // AD-AND-OR and AD-MANDATORY-FOR-KDC, with the same two elements
+    let bytes = &[
+        0x30, 0x5b, 0x30, 0x30, 0xa0, 0x03, 0x02, 0x01, 0x05, 0xa1, 0x29, 0x04, 0x27, 0x30, 0x25,
+        0xa0, 0x03, 0x02, 0x01, 0x01, 0xa1, 0x1e, 0x30, 0x1c, 0x30, 0x0c, 0xa0, 0x04, 0x02, 0x02,
+        0x00, 0x80, 0xa1, 0x04, 0x04, 0x02, 0x01, 0x02, 0x30, 0x0c, 0xa0, 0x04, 0x02, 0x02, 0x00,
+        0x8d, 0xa1, 0x04, 0x04, 0x02, 0x30, 0x00, 0x30, 0x27, 0xa0, 0x03, 0x02, 0x01, 0x08, 0xa1,
+        0x20, 0x04, 0x1e, 0x30, 0x1c, 0x30, 0x0c, 0xa0, 0x04, 0x02, 0x02, 0x00, 0x80, 0xa1, 0x04,
+        0x04, 0x02, 0x01, 0x02, 0x30, 0x0c, 0xa0, 0x04, 0x02, 0x02, 0x00, 0x8d, 0xa1, 0x04, 0x04,
+        0x02, 0x30, 0x00,
+    ];
+    let (rem, ad) = parse_authorization_data(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(ad.len(), 2);
+    let and_or = ad[0].as_and_or().expect("not AD-AND-OR");
+    assert_eq!(and_or.condition_count, 1);
+    assert_eq!(and_or.elements.len(), 2);
+    assert_eq!(and_or.elements[0].ad_type, AdType::AD_WIN2K_PAC);
+    assert_eq!(ad[0].as_mandatory_for_kdc(), None);
+    assert_eq!(ad[1].as_and_or(), None);
+    let mandatory = ad[1]
+        .as_mandatory_for_kdc()
+        .expect("not AD-MANDATORY-FOR-KDC");
+    assert_eq!(mandatory, and_or.elements);
+}
+
+#[test]
+fn test_transited_realms() {
+    let transited = |contents: &'static [u8]| TransitedEncoding {
+        tr_type: TransitedType::DOMAIN_X500_COMPRESS,
+        contents,
+    };
+    assert_eq!(transited(b"").realms(), Some(vec![]));
+    assert_eq!(
+        transited(b"EDU,MIT.,ATHENA.,WASHINGTON.EDU,CS.").realms(),
+        Some(vec![
+            "EDU".to_string(),
+            "MIT.EDU".to_string(),
+            "ATHENA.MIT.EDU".to_string(),
+            "WASHINGTON.EDU".to_string(),
+            "CS.WASHINGTON.EDU".to_string(),
+        ])
+    );
+    assert_eq!(
+        transited(b"/COM,/HP,/APOLLO, /COM/DEC").realms(),
+        Some(vec![
+            "/COM".to_string(),
+            "/COM/HP".to_string(),
+            "/COM/HP/APOLLO".to_string(),
+            "/COM/DEC".to_string(),
+        ])
+    );
+    // null subfields
+    assert_eq!(
+        transited(b",EDU,").realms(),
+        Some(vec![String::new(), "EDU".to_string(), String::new()])
+    );
+    // escaped special characters
+    assert_eq!(
+        transited(br"A\,B,C\\D,EDU,E\.,\ F,\/G").realms(),
+        Some(vec![
+            "A,B".to_string(),
+            r"C\D".to_string(),
+            "EDU".to_string(),
+            "E.".to_string(),
+            " F".to_string(),
+            "/G".to_string(),
+        ])
+    );
+    // dangling escape character
+    assert_eq!(transited(br"EDU\").realms(), None);
+    let other = TransitedEncoding {
+        tr_type: TransitedType(2),
+        contents: b"EDU",
+    };
+    assert_eq!(other.realms(), None);
+}
+
+#[test]
+fn test_parse_last_req() {
+    let bytes = &[
+        0x30, 0x34, 0x30, 0x18, 0xa0, 0x03, 0x02, 0x01, 0x06, 0xa1, 0x11, 0x18, 0x0f, 0x32, 0x30,
+        0x30, 0x35, 0x30, 0x39, 0x31, 0x35, 0x30, 0x39, 0x34, 0x30, 0x32, 0x39, 0x5a, 0x30, 0x18,
+        0xa0, 0x03, 0x02, 0x01, 0xfe, 0xa1, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38,
+        0x31, 0x35, 0x30, 0x39, 0x34, 0x30, 0x32, 0x39, 0x5a,
+    ];
+    let (rem, last_req) = parse_last_req(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(last_req.len(), 2);
+    assert_eq!(last_req[0].lr_type, LastReqType::PASSWORD_EXPIRATION);
+    assert_eq!(last_req[0].lr_value, KerberosTime(&b"20050915094029Z"[..]));
+    assert_eq!(format!("{:?}", last_req[0].lr_type), "password-expiration");
+    // negative values only apply to the KDC that sent the reply
+    assert_eq!(last_req[1].lr_type, LastReqType(-2));
+    assert_eq!(format!("{:?}", last_req[1].lr_type), "LastReqType(-2)");
+}
+
+#[test]
+fn test_pa_enc_timestamp() {
+    let value = &[
+        0x30, 0x11, 0xa0, 0x03, 0x02, 0x01, 0x12, 0xa2, 0x0a, 0x04, 0x08, 0x00, 0x01, 0x02, 0x03,
+        0x04, 0x05, 0x06, 0x07,
+    ];
+    let padata = PAData::new(PAType::PA_ENC_TS, value);
+    let enc = padata
+        .decode_enc_timestamp()
+        .expect("no encrypted timestamp");
+    assert_eq!(enc.etype, EncryptionType::AES256_CTS_HMAC_SHA1_96);
+    assert_eq!(enc.kvno, None);
+    assert_eq!(enc.cipher.as_ref(), &value[11..]);
+    let padata = PAData::new(PAType::PA_ENCRYPTED_CHALLENGE, value);
+    assert_eq!(padata.decode_enc_timestamp(), None);
+    // decrypted PA-ENC-TS-ENC
+    let bytes = &[
+        0x30, 0x1a, 0xa0, 0x11, 0x18, 0x0f, 0x32, 0x30, 0x30, 0x35, 0x30, 0x38, 0x31, 0x36, 0x30,
+        0x39, 0x34, 0x30, 0x32, 0x39, 0x5a, 0xa1, 0x05, 0x02, 0x03, 0x01, 0xe2, 0x40,
+    ];
+    let (rem, ts) = parse_pa_enc_ts_enc(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(ts.patimestamp, KerberosTime(&b"20050816094029Z"[..]));
+    assert_eq!(ts.pausec, Some(123_456));
+    // without pausec
+    let mut bytes = bytes[..21].to_vec();
+    bytes[1] = 0x13;
+    let (_, ts) = parse_pa_enc_ts_enc(&bytes).expect("parsing failed");
+    assert_eq!(ts.pausec, None);
+}
+
+#[test]
+fn test_ticket_flags() {
+    let (_, part) = parse_enc_ticket_part(ENC_TICKET_PART).expect("parsing failed");
+    let flags = part.flags_typed();
+    assert_eq!(flags, TicketFlags(0x40e1_0000));
+    assert!(flags.is_forwardable());
+    assert!(flags.is_renewable());
+    assert!(flags.is_initial());
+    assert!(flags.is_pre_authent());
+    assert!(!flags.is_postdated());
+    assert!(!flags.is_invalid());
+    assert!(!flags.is_hw_authent());
+    assert!(!flags.is_ok_as_delegate());
+    assert!(TicketFlags(0x0004_0000).is_ok_as_delegate());
+    assert!(flags.is_set(15));
+    assert!(!flags.is_set(32));
+    assert_eq!(
+        flags.names(),
+        vec!["forwardable", "renewable", "initial", "pre-authent"]
+    );
+    assert!(TicketFlags(0x8000_0000).names().is_empty());
+    let (_, part) = parse_enc_as_rep_part(ENC_AS_REP_PART).expect("parsing failed");
+    assert_eq!(part.flags_typed(), TicketFlags(0x40e0_0000));
+}
+
+#[test]
+fn test_enc_part_is_renewable() {
+    let (_, part) = parse_enc_as_rep_part(ENC_AS_REP_PART).expect("parsing failed");
+    assert!(part.is_renewable());
+    let (_, part) = parse_enc_ticket_part(ENC_TICKET_PART).expect("parsing failed");
+    assert!(part.is_renewable());
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_enc_part_renew_till_utc() {
+    let (_, mut part) = parse_enc_as_rep_part(ENC_AS_REP_PART).expect("parsing failed");
+    assert_eq!(
+        part.renew_till_utc(),
+        Ok(KerberosTime(&b"20050823094029Z"[..]).to_datetime())
+    );
+    // clear the RENEWABLE flag
+    let mut bytes = ENC_AS_REP_PART.to_vec();
+    assert_eq!(bytes[95], 0xe0);
+    bytes[95] = 0x60;
+    let (_, part_not_renewable) = parse_enc_as_rep_part(&bytes).expect("parsing failed");
+    assert!(!part_not_renewable.is_renewable());
+    assert_eq!(
+        part_not_renewable.renew_till_utc(),
+        Err(TicketTimeError::RenewTillWithoutRenewable)
+    );
+    part.renew_till = None;
+    assert_eq!(part.renew_till_utc(), Ok(None));
+    part.renew_till = Some(KerberosTime(&b"2005082309402"[..]));
+    assert_eq!(part.renew_till_utc(), Err(TicketTimeError::InvalidTime));
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_enc_part_is_postdated() {
+    let (_, part) = parse_enc_ticket_part(ENC_TICKET_PART).expect("parsing failed");
+    let starttime = part
+        .starttime
+        .and_then(|t| t.to_datetime())
+        .expect("no starttime");
+    assert_eq!(part.is_postdated(starttime), Some(false));
+    let before = starttime - chrono::Duration::hours(1);
+    assert_eq!(part.is_postdated(before), Some(true));
+    // set the POSTDATED flag
+    let mut bytes = ENC_TICKET_PART.to_vec();
+    assert_eq!(bytes[11], 0x40);
+    bytes[11] |= 0x02;
+    let (_, part) = parse_enc_ticket_part(&bytes).expect("parsing failed");
+    assert_eq!(part.is_postdated(starttime), Some(true));
+    // no starttime in this one
+    let (_, part) = parse_enc_as_rep_part(ENC_AS_REP_PART).expect("parsing failed");
+    assert_eq!(part.is_postdated(before), None);
+}
+
+#[test]
+fn test_enc_ticket_part_has_pac() {
+    let (_, mut part) = parse_enc_ticket_part(ENC_TICKET_PART).expect("parsing failed");
+    assert!(!part.has_pac());
+    // AD-IF-RELEVANT containing an empty AD-WIN2K-PAC
+    let if_relevant = [
+        0x30, 0x0c, 0x30, 0x0a, 0xa0, 0x04, 0x02, 0x02, 0x00, 0x80, 0xa1, 0x02, 0x04, 0x00,
+    ];
+    part.authorization_data = Some(vec![AuthorizationDataElement {
+        ad_type: AdType::AD_IF_RELEVANT,
+        ad_data: &if_relevant,
+    }]);
+    assert!(part.has_pac());
+    part.authorization_data = Some(vec![AuthorizationDataElement {
+        ad_type: AdType::AD_WIN2K_PAC,
+        ad_data: &[],
+    }]);
+    assert!(part.has_pac());
+    part.authorization_data = None;
+    assert!(!part.has_pac());
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_enc_ticket_part_suspicious_indicators() {
+    let (_, part) = parse_enc_ticket_part(ENC_TICKET_PART).expect("parsing failed");
+    let authtime = part.authtime.to_datetime().expect("invalid authtime");
+    let now = authtime + chrono::Duration::hours(1);
+    // the test ticket has no PAC
+    assert_eq!(
+        part.suspicious_indicators(now),
+        vec![TicketAnomaly::MissingPac]
+    );
+    let later = authtime + chrono::Duration::days(30);
+    let mut forged = parse_enc_ticket_part(ENC_TICKET_PART)
+        .expect("parsing failed")
+        .1;
+    forged.endtime = KerberosTime(&b"20150816094029Z"[..]);
+    assert_eq!(
+        forged.suspicious_indicators(later),
+        vec![
+            TicketAnomaly::LongLifetime,
+            TicketAnomaly::OldAuthtime,
+            TicketAnomaly::MissingPac
+        ]
+    );
+    // set the INVALID flag
+    let mut bytes = ENC_TICKET_PART.to_vec();
+    assert_eq!(bytes[11], 0x40);
+    bytes[11] |= 0x01;
+    let (_, part) = parse_enc_ticket_part(&bytes).expect("parsing failed");
+    assert_eq!(
+        part.suspicious_indicators(now),
+        vec![TicketAnomaly::MissingPac, TicketAnomaly::InconsistentFlags]
+    );
+}
+
+#[test]
+fn test_message_pvno_msg_type_checks() {
+    let custom5 = Err(Err::Error(BerError::Custom(5)));
+    let value_error = Err(Err::Error(BerError::BerValueError));
+    // AS-REQ
+    let mut bytes = AS_REQ.to_vec();
+    bytes[12] = 4;
+    assert_eq!(parse_as_req(&bytes).map(|_| ()), custom5);
+    let mut bytes = AS_REQ.to_vec();
+    bytes[17] = 12;
+    assert_eq!(parse_as_req(&bytes).map(|_| ()), value_error);
+    let mut tgs = bytes.clone();
+    tgs[0] = 0x6c;
+    tgs[17] = 10;
+    assert_eq!(parse_tgs_req(&tgs).map(|_| ()), value_error);
+    bytes[17] = 11;
+    assert_eq!(parse_kdc_req(&bytes[4..]).map(|_| ()), value_error);
+    // AS-REP
+    let mut bytes = AS_REP.to_vec();
+    bytes[12] = 4;
+    assert_eq!(parse_as_rep(&bytes).map(|_| ()), custom5);
+    let mut bytes = AS_REP.to_vec();
+    bytes[17] = 13;
+    assert_eq!(parse_as_rep(&bytes).map(|_| ()), value_error);
+    bytes[17] = 10;
+    assert_eq!(parse_kdc_rep(&bytes[4..]).map(|_| ()), value_error);
+    // KRB-ERROR
+    let mut bytes = KRB_ERROR.to_vec();
+    bytes[10] = 4;
+    assert_eq!(parse_krb_error(&bytes).map(|_| ()), custom5);
+    let mut bytes = KRB_ERROR.to_vec();
+    bytes[15] = 31;
+    assert_eq!(parse_krb_error(&bytes).map(|_| ()), value_error);
+}